@@ -1,19 +1,79 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error};
 
+use chrono::{Date, Datelike, Duration, Local, NaiveDate, Utc, Weekday};
 use egui::Context;
 use egui_extras::RetainedImage;
 use rusqlite::Connection;
 
-use crate::lang::{get_language_hashmaps, Languages};
+use crate::backup::{create_auto_backup, Backup};
+use crate::balance::Period;
+use crate::chart::{Bucket, ChartSelection};
+use crate::csv::ImportMode;
+use crate::db::setup_db;
+use crate::format::Locale;
+use crate::lang::{get_language_hashmaps, Language};
 use crate::menu::{display_menu, Menu};
+use crate::paths::DataDir;
+use crate::planner::{DEFAULT_MAX_PER_TYPE, DEFAULT_SESSION_REPS};
+use crate::schedule::Schedule;
 use crate::settings::load_settings;
-use crate::skills::{Skill, SkillHashtags};
+use crate::skills::{Skill, SkillHashtags, SkillTypes};
+use crate::workout::TimedWorkout;
 
 pub struct RingFitApp {
     pub skills: Vec<Skill>,
     pub input_reps: Vec<String>,
+    // The new goals typed into the goals menu, kept apart from the reps of the other menus.
+    pub input_goals: Vec<String>,
     pub menu: Option<Menu>,
-    pub language: Languages,
+    // The last error that happened, shown to the user until they dismiss it.
+    pub error_message: Option<String>,
+    // The outcome of the last import or export, shown in that menu.
+    pub status_message: Option<String>,
+    // The file to import from or export to.
+    pub file_path: String,
+    // Workouts read from a file, waiting for the user to confirm the import.
+    pub pending_import: Option<Vec<TimedWorkout>>,
+    pub import_mode: ImportMode,
+    // The file to save a backup to or restore it from.
+    pub backup_path: String,
+    // A backup read from a file, waiting for the user to confirm restoring it.
+    pub pending_restore: Option<Backup>,
+    // What the charts show and how the history is grouped in them.
+    pub chart_selection: ChartSelection,
+    pub chart_bucket: Bucket,
+    // How many days back the workouts are used for the forecast in `view_progess`.
+    pub forecast_days: i64,
+    // The hour a new day starts in the stats.
+    pub day_start_hour: u32,
+    // The first day of the month shown in the calendar.
+    pub calendar_month: NaiveDate,
+    // The time span of the balance report.
+    pub balance_period: Period,
+    // The size of the session the planner suggests, in reps or in minutes.
+    pub plan_amount: usize,
+    pub plan_in_minutes: bool,
+    pub plan_max_per_type: usize,
+    pub schedule: Option<Schedule>,
+    // The inputs for making a new schedule.
+    pub schedule_date: Date<Utc>,
+    pub schedule_rest_days: Vec<Weekday>,
+    // The inputs for changing many goals at once, no type means every skill.
+    pub goal_type: Option<SkillTypes>,
+    pub goal_amount: usize,
+    // The name to save the current goals as.
+    pub goal_profile_name: String,
+    // The inputs for adding a custom skill, the goal can be left empty.
+    pub custom_skill_name: String,
+    pub custom_skill_type: SkillTypes,
+    pub custom_skill_hashtags: [SkillHashtags; 3],
+    pub custom_skill_goal: String,
+    // The Adventure Mode level of the player, and the one being entered in `unlocks`.
+    pub player_level: Option<usize>,
+    pub player_level_input: usize,
+    pub language: Language,
+    // How dates and numbers are written in the language.
+    pub locale: Locale,
     // We load some images on startup.
     pub images: Vec<RetainedImage>,
     // These are set so that we dont have to read them from the database every time.
@@ -23,21 +83,27 @@ pub struct RingFitApp {
     pub menu_names: HashMap<String, String>,
     // Same here, we dont want to reconnect every time.
     pub db_connection: Connection,
+    // Where the database and settings are stored.
+    pub data_dir: DataDir,
+    // Set if there is data from older versions in the working directory, which we offer to move.
+    pub legacy_data: bool,
 }
 
-impl Default for RingFitApp {
-    fn default() -> Self {
-        let settings = load_settings().expect("Could not read settings.json file.");
+impl RingFitApp {
+    #[must_use]
+    /// Loads everything from the data directory, which has to be set up already.
+    pub fn new(data_dir: DataDir) -> Self {
+        let settings = load_settings(&data_dir).expect("Could not read settings.json file.");
 
         let connection =
-            Connection::open("./db/database.db").expect("Could not open connection to database.");
+            Connection::open(data_dir.database()).expect("Could not open connection to database.");
 
         // Getting every skill available.
         let all_skills = Skill::get_all_skills(&connection);
 
         // Getting the translations to save in the hashmaps.
         let (skill_hashmap, hashtag_hashmap, menu_hashmap) =
-            get_language_hashmaps(&connection, settings.language);
+            get_language_hashmaps(&connection, &settings.language);
 
         // Loading some icons to display them later on.
         let image_bytes = vec![
@@ -64,17 +130,106 @@ impl Default for RingFitApp {
         ];
 
         Self {
+            locale: Locale::from_strings(&menu_hashmap),
             input_reps: vec!["".into(); all_skills.len()],
+            input_goals: vec!["".into(); all_skills.len()],
             skills: all_skills,
             menu_names: menu_hashmap,
             hashtag_names: hashtag_hashmap,
             skill_names: skill_hashmap,
             images: image_bytes,
             menu: None,
+            error_message: None,
+            status_message: None,
+            file_path: data_dir.path.join("workouts.csv").display().to_string(),
+            pending_import: None,
+            import_mode: ImportMode::Merge,
+            backup_path: data_dir.path.join("backup.json").display().to_string(),
+            pending_restore: None,
+            chart_selection: ChartSelection::All,
+            chart_bucket: Bucket::Week,
+            forecast_days: settings.forecast_days,
+            day_start_hour: settings.day_start_hour,
+            calendar_month: Local::now().naive_local().date().with_day(1).unwrap(),
+            balance_period: Period::Month,
+            plan_amount: DEFAULT_SESSION_REPS,
+            plan_in_minutes: false,
+            plan_max_per_type: DEFAULT_MAX_PER_TYPE,
+            schedule_date: match &settings.schedule {
+                Some(schedule) => Date::from_utc(schedule.target_date, Utc),
+                None => Utc::today() + Duration::days(365),
+            },
+            schedule_rest_days: match &settings.schedule {
+                Some(schedule) => schedule.rest_days.clone(),
+                None => vec![Weekday::Sun],
+            },
+            schedule: settings.schedule,
+            goal_type: None,
+            goal_amount: 6000,
+            goal_profile_name: String::new(),
+            custom_skill_name: String::new(),
+            custom_skill_type: SkillTypes::Arms,
+            custom_skill_hashtags: [
+                SkillHashtags::Empty,
+                SkillHashtags::Empty,
+                SkillHashtags::Empty,
+            ],
+            custom_skill_goal: String::new(),
+            player_level: settings.player_level,
+            player_level_input: settings.player_level.unwrap_or(1),
             language: settings.language,
             db_connection: connection,
+            legacy_data: data_dir.has_legacy_data(),
+            data_dir,
         }
     }
+
+    /// Saves an automatic backup before something destructive happens.
+    /// If that does not work the error is shown and false is returned,
+    /// in which case the destructive action should not happen either.
+    pub fn auto_backup(&mut self) -> bool {
+        match create_auto_backup(&self.db_connection, &self.data_dir) {
+            Ok(_) => true,
+            Err(e) => {
+                self.error_message = Some(format!(
+                    "{}: {}",
+                    self.menu_names
+                        .get("backup_error")
+                        .unwrap_or(&"Could not save a backup".to_owned()),
+                    e
+                ));
+                false
+            }
+        }
+    }
+
+    /// Reads the skills from the database again, after some were added or deleted.
+    pub fn reload_skills(&mut self) {
+        self.skills = Skill::get_all_skills(&self.db_connection);
+        self.input_reps = vec!["".into(); self.skills.len()];
+        self.input_goals = vec!["".into(); self.skills.len()];
+
+        let (skill_hashmap, _, _) = get_language_hashmaps(&self.db_connection, &self.language);
+        self.skill_names = skill_hashmap;
+    }
+
+    /// Moves the data from older versions in the working directory into the data directory,
+    /// and reloads everything from there.
+    pub fn move_legacy_data(&mut self) -> Result<(), Box<dyn Error>> {
+        // The connection has to be closed first, the database file gets replaced.
+        self.db_connection = Connection::open_in_memory()?;
+
+        // The old database might be from before some migrations.
+        let result = self
+            .data_dir
+            .move_legacy_data()
+            .and_then(|_| setup_db(&self.data_dir));
+
+        // Whether that worked or not, we reload everything from the data directory.
+        *self = Self::new(self.data_dir.clone());
+
+        result
+    }
 }
 
 impl eframe::App for RingFitApp {
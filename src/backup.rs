@@ -0,0 +1,635 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    fs::{create_dir_all, read_dir, read_to_string, remove_file, write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    custom::insert_custom_skill,
+    lang::Language,
+    paths::DataDir,
+    settings::{load_settings, save_settings, Settings},
+    skills::Skill,
+    workout::{
+        get_adjustments_from_db, get_workouts_from_db, insert_workout_to_db, save_adjustment_to_db,
+        Workout,
+    },
+};
+
+/// The version of the backup format, increased whenever it changes.
+pub const BACKUP_VERSION: usize = 2;
+/// How many automatic backups are kept, the oldest ones get deleted first.
+pub const MAX_AUTO_BACKUPS: usize = 10;
+
+/// Everything the tracker knows, in a single file.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: usize,
+    pub created: DateTime<Local>,
+    pub settings: Settings,
+    pub skills: Vec<Skill>,
+    pub workouts: Vec<BackupWorkout>,
+    pub adjustments: Vec<BackupAdjustment>,
+    // Backups of version 1 don't have these, restoring them keeps the current goal profiles.
+    #[serde(default)]
+    pub goal_profiles: Option<Vec<BackupGoalProfile>>,
+}
+
+/// A workout in a backup, the skills are only referenced by name.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupWorkout {
+    pub timestamp: DateTime<Local>,
+    pub skills: Vec<(String, usize)>,
+}
+
+/// A manual adjustment in a backup.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupAdjustment {
+    pub timestamp: DateTime<Local>,
+    pub skill: String,
+    pub old_reps: i64,
+    pub new_reps: i64,
+}
+
+/// A goal profile in a backup, with the goal of every skill by name.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupGoalProfile {
+    pub name: String,
+    pub goals: Vec<(String, usize)>,
+}
+
+/// What restoring a backup would change, the current values first and the values of the backup second.
+#[derive(Debug)]
+pub struct BackupDiff {
+    pub language: (Language, Language),
+    pub workouts: (usize, usize),
+    pub adjustments: (usize, usize),
+    // Only the skills where the completed or goal reps change.
+    pub skills: Vec<SkillDiff>,
+    // Custom skills that are not in the backup, which get deleted.
+    pub removed_skills: Vec<Skill>,
+}
+
+#[derive(Debug)]
+pub struct SkillDiff {
+    pub skill: Skill,
+    pub completed_reps: (usize, usize),
+    pub goal_reps: (usize, usize),
+}
+
+/// Collects everything in the database and the settings into a backup.
+pub fn create_backup(
+    connection: &Connection,
+    data_dir: &DataDir,
+) -> Result<Backup, Box<dyn Error>> {
+    Ok(Backup {
+        version: BACKUP_VERSION,
+        created: chrono::offset::Local::now(),
+        settings: load_settings(data_dir)?,
+        skills: Skill::get_all_skills(connection),
+        // The history is stored oldest first, so it gets restored in the same order.
+        workouts: get_workouts_from_db(connection)
+            .into_iter()
+            .rev()
+            .map(|(timestamp, workout)| BackupWorkout {
+                timestamp,
+                skills: workout
+                    .skill
+                    .into_iter()
+                    .map(|(skill, reps)| (skill.name, reps))
+                    .collect(),
+            })
+            .collect(),
+        adjustments: get_adjustments_from_db(connection)
+            .into_iter()
+            .rev()
+            .map(|(timestamp, adjustment)| BackupAdjustment {
+                timestamp,
+                skill: adjustment.skill.name,
+                old_reps: adjustment.old_reps,
+                new_reps: adjustment.new_reps,
+            })
+            .collect(),
+        goal_profiles: Some(get_backup_goal_profiles(connection)?),
+    })
+}
+
+/// Gets every goal profile with the goals of its skills, sorted by name.
+fn get_backup_goal_profiles(
+    connection: &Connection,
+) -> Result<Vec<BackupGoalProfile>, Box<dyn Error>> {
+    let mut stmt = connection
+        .prepare("SELECT name, skill, goal_reps FROM goal_profiles ORDER BY name, skill")?;
+
+    let mut profiles: Vec<BackupGoalProfile> = Vec::new();
+    for row in stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))? {
+        let (name, skill, goal_reps): (String, String, usize) = row?;
+        match profiles.last_mut() {
+            Some(profile) if profile.name == name => profile.goals.push((skill, goal_reps)),
+            _ => profiles.push(BackupGoalProfile {
+                name,
+                goals: vec![(skill, goal_reps)],
+            }),
+        }
+    }
+
+    Ok(profiles)
+}
+
+/// Writes a backup into a file.
+pub fn write_backup(backup: &Backup, path: &Path) -> Result<(), Box<dyn Error>> {
+    write(path, serde_json::to_string_pretty(backup)?)?;
+
+    Ok(())
+}
+
+/// Reads a backup from a file and makes sure it can be restored into this database.
+pub fn read_backup(connection: &Connection, path: &Path) -> Result<Backup, Box<dyn Error>> {
+    let backup: Backup = serde_json::from_str(&read_to_string(path)?)?;
+
+    validate_backup(connection, &backup)?;
+
+    Ok(backup)
+}
+
+/// Checks that a backup is complete and only references skills that exist.
+pub fn validate_backup(connection: &Connection, backup: &Backup) -> Result<(), Box<dyn Error>> {
+    if backup.version > BACKUP_VERSION {
+        return Err(format!(
+            "The backup has version {}, but this version of the program only knows up to version {}.",
+            backup.version, BACKUP_VERSION
+        )
+        .into());
+    }
+
+    let known_skills = Skill::get_all_skills(connection);
+
+    let mut backup_skills = HashSet::new();
+    let mut lowercase_names = HashSet::new();
+    for skill in &backup.skills {
+        // Custom skills that are missing get added when restoring, but skills are found by their name
+        // without caring about the case, so there can't be another one that only differs in case.
+        match known_skills
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(&skill.name))
+        {
+            Some(known) if known.name != skill.name => {
+                return Err(format!(
+                    "The backup contains the skill \"{}\", but it is called \"{}\" here.",
+                    skill.name, known.name
+                )
+                .into())
+            }
+            None if !skill.custom => {
+                return Err(
+                    format!("The backup contains the unknown skill \"{}\".", skill.name).into(),
+                )
+            }
+            _ => (),
+        }
+        if !lowercase_names.insert(skill.name.to_lowercase()) {
+            return Err(format!("The backup contains the skill \"{}\" twice.", skill.name).into());
+        }
+        if skill.goal_reps == 0 {
+            return Err(format!(
+                "The backup contains a goal of 0 reps for \"{}\".",
+                skill.name
+            )
+            .into());
+        }
+        backup_skills.insert(skill.name.clone());
+    }
+
+    for profile in backup.goal_profiles.iter().flatten() {
+        if let Some((name, _)) = profile.goals.iter().find(|(_, goal_reps)| *goal_reps == 0) {
+            return Err(format!(
+                "The goal profile \"{}\" in the backup has a goal of 0 reps for \"{}\".",
+                profile.name, name
+            )
+            .into());
+        }
+    }
+
+    let referenced_skills = backup
+        .workouts
+        .iter()
+        .flat_map(|w| w.skills.iter().map(|(name, _)| name))
+        .chain(backup.adjustments.iter().map(|a| &a.skill))
+        .chain(
+            backup
+                .goal_profiles
+                .iter()
+                .flatten()
+                .flat_map(|p| p.goals.iter().map(|(name, _)| name)),
+        );
+
+    for name in referenced_skills {
+        if !backup_skills.contains(name) {
+            return Err(format!(
+                "The history in the backup contains the unknown skill \"{}\".",
+                name
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Compares the backup with the current state of the database.
+pub fn diff_backup(
+    connection: &Connection,
+    data_dir: &DataDir,
+    backup: &Backup,
+) -> Result<BackupDiff, Box<dyn Error>> {
+    let (removed_skills, kept_skills): (Vec<Skill>, Vec<Skill>) = Skill::get_all_skills(connection)
+        .into_iter()
+        .partition(|skill| skill.custom && !backup.skills.contains(skill));
+
+    let skills = kept_skills
+        .into_iter()
+        .filter_map(|skill| {
+            // Skills that are not in the backup lose their reps, but keep their goal.
+            let (backup_completed, backup_goal) =
+                match backup.skills.iter().find(|s| s.name == skill.name) {
+                    Some(backup_skill) => (backup_skill.completed_reps, backup_skill.goal_reps),
+                    None => (0, skill.goal_reps),
+                };
+
+            if backup_completed == skill.completed_reps && backup_goal == skill.goal_reps {
+                return None;
+            }
+
+            Some(SkillDiff {
+                completed_reps: (skill.completed_reps, backup_completed),
+                goal_reps: (skill.goal_reps, backup_goal),
+                skill,
+            })
+        })
+        .collect();
+
+    Ok(BackupDiff {
+        language: (
+            load_settings(data_dir)?.language,
+            backup.settings.language.clone(),
+        ),
+        workouts: (
+            get_workouts_from_db(connection).len(),
+            backup.workouts.len(),
+        ),
+        adjustments: (
+            get_adjustments_from_db(connection).len(),
+            backup.adjustments.len(),
+        ),
+        skills,
+        removed_skills,
+    })
+}
+
+/// Replaces the history, progress, custom skills and goal profiles in the database with the backup,
+/// and restores the settings. The database changes happen in one transaction, and the settings are written
+/// before it is committed, so if anything goes wrong nothing is changed.
+pub fn restore_backup(
+    connection: &Connection,
+    data_dir: &DataDir,
+    backup: &Backup,
+) -> Result<(), Box<dyn Error>> {
+    validate_backup(connection, backup)?;
+
+    let transaction = connection.unchecked_transaction()?;
+
+    // `validate_backup` made sure no skill here differs from one in the backup only in case.
+    let known_skills = Skill::get_all_skills(&transaction);
+    for skill in backup.skills.iter().filter(|s| !known_skills.contains(s)) {
+        insert_custom_skill(&transaction, skill)?;
+    }
+
+    let all_skills = Skill::get_all_skills(&transaction);
+    let find_skill = |name: &str| {
+        all_skills
+            .iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| format!("The skill \"{}\" does not exist.", name))
+    };
+
+    transaction.execute_batch(
+        "
+            DELETE FROM workout_skills;
+            DELETE FROM workouts;
+            DELETE FROM rep_adjustments;
+        ",
+    )?;
+
+    // Custom skills that are not in the backup did not exist when it was made.
+    for skill in known_skills
+        .iter()
+        .filter(|s| s.custom && !backup.skills.contains(s))
+    {
+        transaction.execute("DELETE FROM skills WHERE name = ?", [&skill.name])?;
+        transaction.execute("DELETE FROM goal_profiles WHERE skill = ?", [&skill.name])?;
+        transaction.execute("DELETE FROM skill_hashtags WHERE skill = ?", [&skill.name])?;
+    }
+
+    // Skills that are not in the backup had no reps when it was made.
+    transaction.execute("UPDATE skills SET completed_reps = 0", ())?;
+    for skill in &backup.skills {
+        transaction.execute(
+            "UPDATE skills SET goal_reps = :goal_reps, completed_reps = :completed_reps WHERE name = :name",
+            (skill.goal_reps, skill.completed_reps, skill.name.clone()),
+        )?;
+    }
+
+    for backup_workout in &backup.workouts {
+        let mut workout = Workout {
+            id: 0,
+            skill: Vec::new(),
+        };
+        for (name, reps) in &backup_workout.skills {
+            workout.skill.push((find_skill(name)?.clone(), *reps));
+        }
+
+        insert_workout_to_db(&transaction, backup_workout.timestamp, &workout)?;
+    }
+
+    for adjustment in &backup.adjustments {
+        save_adjustment_to_db(
+            &transaction,
+            adjustment.timestamp,
+            find_skill(&adjustment.skill)?,
+            adjustment.old_reps,
+            adjustment.new_reps,
+        )?;
+    }
+
+    if let Some(goal_profiles) = &backup.goal_profiles {
+        transaction.execute("DELETE FROM goal_profiles", ())?;
+        for profile in goal_profiles {
+            for (skill, goal_reps) in &profile.goals {
+                transaction.execute(
+                    "INSERT INTO goal_profiles VALUES (:name, :skill, :goal_reps)",
+                    (&profile.name, skill, goal_reps),
+                )?;
+            }
+        }
+        // Skills that came after the backup keep their current goal in every profile.
+        transaction.execute(
+            "
+                INSERT INTO goal_profiles
+                SELECT profiles.name, skills.name, skills.goal_reps
+                FROM (SELECT DISTINCT name FROM goal_profiles) AS profiles, skills
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM goal_profiles
+                    WHERE goal_profiles.name = profiles.name AND goal_profiles.skill = skills.name
+                )
+            ",
+            (),
+        )?;
+    }
+
+    // If writing the settings fails, the transaction is rolled back when it gets dropped.
+    let old_settings = load_settings(data_dir)?;
+    save_settings(data_dir, &backup.settings)?;
+    if let Err(e) = transaction.commit() {
+        save_settings(data_dir, &old_settings)?;
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Gets every automatic backup, the newest first.
+pub fn get_auto_backups(data_dir: &DataDir) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = match read_dir(data_dir.backups()) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("auto-") && n.ends_with(".json"))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    // The file names start with the time, so sorting them by name sorts them by time.
+    backups.sort();
+    backups.reverse();
+
+    backups
+}
+
+/// Saves a backup into the backups folder before something destructive happens,
+/// and deletes the oldest automatic backups if there are more than `MAX_AUTO_BACKUPS`.
+pub fn create_auto_backup(
+    connection: &Connection,
+    data_dir: &DataDir,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let backup = create_backup(connection, data_dir)?;
+
+    create_dir_all(data_dir.backups())?;
+    let path = data_dir.backups().join(format!(
+        "auto-{}.json",
+        backup.created.format("%Y-%m-%d_%H-%M-%S-%3f")
+    ));
+    write_backup(&backup, &path)?;
+
+    for old_backup in get_auto_backups(data_dir).iter().skip(MAX_AUTO_BACKUPS) {
+        remove_file(old_backup)?;
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::{
+        custom::add_custom_skill,
+        db::test::test_connection,
+        goals::{
+            get_active_profile, get_goal_profiles, save_goal_profile, DOUBLE_PROFILE, TITLE_PROFILE,
+        },
+        skills::{SkillHashtags, SkillTypes},
+        workout::{log_workout_to_db, save_manual_reps_to_db},
+    };
+
+    pub fn test_data_dir(name: &str) -> DataDir {
+        let data_dir = DataDir::new(std::env::temp_dir().join(format!(
+            "rfa-tracker-test-{}-{}",
+            name,
+            std::process::id()
+        )));
+        let _ = std::fs::remove_dir_all(&data_dir.path);
+        create_dir_all(&data_dir.path).unwrap();
+        save_settings(&data_dir, &Settings::default()).unwrap();
+        data_dir
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let connection = test_connection();
+        let data_dir = test_data_dir("restore");
+        let skills = Skill::get_all_skills(&connection);
+
+        let mut reps = vec!["".to_owned(); skills.len()];
+        reps[0] = "10".into();
+        log_workout_to_db(&connection, &skills, &reps).unwrap();
+        reps[0] = "".into();
+        reps[1] = "50".into();
+        save_manual_reps_to_db(&connection, &skills, &reps).unwrap();
+
+        let path = data_dir.path.join("backup.json");
+        write_backup(&create_backup(&connection, &data_dir).unwrap(), &path).unwrap();
+
+        // Losing everything after the backup.
+        connection
+            .execute_batch(
+                "DELETE FROM workout_skills; DELETE FROM workouts; UPDATE skills SET completed_reps = 0;",
+            )
+            .unwrap();
+
+        let backup = read_backup(&connection, &path).unwrap();
+        let diff = diff_backup(&connection, &data_dir, &backup).unwrap();
+        assert_eq!(diff.workouts, (0, 1));
+        assert_eq!(diff.adjustments, (1, 1));
+        assert_eq!(diff.skills.len(), 2);
+        assert_eq!(diff.skills[0].completed_reps, (0, 10));
+
+        restore_backup(&connection, &data_dir, &backup).unwrap();
+
+        let restored = Skill::get_all_skills(&connection);
+        assert_eq!(restored[0].completed_reps, 10);
+        assert_eq!(restored[1].completed_reps, 50);
+        assert_eq!(get_workouts_from_db(&connection).len(), 1);
+        assert_eq!(get_adjustments_from_db(&connection).len(), 1);
+
+        // Goal profiles made after the backup are gone again, and skills missing in it lose their reps.
+        save_goal_profile(&connection, "Later").unwrap();
+        restored[2].set_reps(&connection, 70).unwrap();
+        let mut backup = read_backup(&connection, &path).unwrap();
+        backup.skills.retain(|s| s.name != restored[2].name);
+        for profile in backup.goal_profiles.iter_mut().flatten() {
+            profile.goals.retain(|(name, _)| *name != restored[2].name);
+        }
+        let diff = diff_backup(&connection, &data_dir, &backup).unwrap();
+        assert!(diff.skills.iter().any(|d| d.completed_reps == (70, 0)));
+
+        restore_backup(&connection, &data_dir, &backup).unwrap();
+        assert_eq!(Skill::get_all_skills(&connection)[2].completed_reps, 0);
+        assert_eq!(
+            get_goal_profiles(&connection).unwrap(),
+            vec![DOUBLE_PROFILE.to_owned(), TITLE_PROFILE.to_owned()]
+        );
+        // The skill that was left out still has a goal in every profile.
+        assert_eq!(
+            get_active_profile(&connection).unwrap(),
+            Some(TITLE_PROFILE.to_owned())
+        );
+
+        let _ = std::fs::remove_dir_all(&data_dir.path);
+    }
+
+    #[test]
+    fn test_restore_custom_skills() {
+        let connection = test_connection();
+        let data_dir = test_data_dir("custom");
+
+        let pushups = Skill::new_custom(
+            "Pushups",
+            SkillTypes::Arms,
+            [
+                SkillHashtags::Chest,
+                SkillHashtags::Empty,
+                SkillHashtags::Empty,
+            ],
+            None,
+        );
+        add_custom_skill(&connection, &pushups).unwrap();
+        let skills = Skill::get_all_skills(&connection);
+        let mut reps = vec!["".to_owned(); skills.len()];
+        *reps.last_mut().unwrap() = "25".into();
+        log_workout_to_db(&connection, &skills, &reps).unwrap();
+
+        let backup = create_backup(&connection, &data_dir).unwrap();
+
+        // A new database does not have the skill yet, restoring adds it.
+        let new_connection = test_connection();
+        restore_backup(&new_connection, &data_dir, &backup).unwrap();
+
+        let restored = Skill::get_all_skills(&new_connection);
+        let restored_pushups = restored.iter().find(|s| s.name == "Pushups").unwrap();
+        assert!(restored_pushups.custom);
+        assert_eq!(restored_pushups.completed_reps, 25);
+        assert_eq!(get_workouts_from_db(&new_connection).len(), 1);
+
+        // Custom skills that came after the backup are deleted.
+        let backup = create_backup(&test_connection(), &data_dir).unwrap();
+        let diff = diff_backup(&new_connection, &data_dir, &backup).unwrap();
+        assert_eq!(diff.removed_skills, vec![pushups.clone()]);
+        assert!(diff.skills.iter().all(|d| d.skill != pushups));
+        restore_backup(&new_connection, &data_dir, &backup).unwrap();
+        assert!(!Skill::get_all_skills(&new_connection).contains(&pushups));
+        assert!(get_backup_goal_profiles(&new_connection)
+            .unwrap()
+            .iter()
+            .all(|p| p.goals.iter().all(|(name, _)| name != "Pushups")));
+
+        // A custom skill can't come back with another case than one that exists.
+        let mut plank = pushups.clone();
+        plank.name = "plank".into();
+        let mut backup = create_backup(&new_connection, &data_dir).unwrap();
+        backup.skills.push(plank);
+        assert!(validate_backup(&new_connection, &backup).is_err());
+
+        let _ = std::fs::remove_dir_all(&data_dir.path);
+    }
+
+    #[test]
+    fn test_invalid_backup() {
+        let connection = test_connection();
+        let data_dir = test_data_dir("invalid");
+
+        let mut backup = create_backup(&connection, &data_dir).unwrap();
+        backup.workouts.push(BackupWorkout {
+            timestamp: chrono::offset::Local::now(),
+            skills: vec![("Not a skill".into(), 10)],
+        });
+        assert!(validate_backup(&connection, &backup).is_err());
+
+        let mut backup = create_backup(&connection, &data_dir).unwrap();
+        backup.version = BACKUP_VERSION + 1;
+        assert!(validate_backup(&connection, &backup).is_err());
+
+        // A goal of 0 reps would make the percentages divide by 0.
+        let mut backup = create_backup(&connection, &data_dir).unwrap();
+        backup.skills[0].goal_reps = 0;
+        assert!(validate_backup(&connection, &backup).is_err());
+
+        let mut backup = create_backup(&connection, &data_dir).unwrap();
+        backup.goal_profiles.as_mut().unwrap()[0].goals[0].1 = 0;
+        assert!(validate_backup(&connection, &backup).is_err());
+
+        let _ = std::fs::remove_dir_all(&data_dir.path);
+    }
+
+    #[test]
+    fn test_auto_backup_rotation() {
+        let connection = test_connection();
+        let data_dir = test_data_dir("rotation");
+
+        for _ in 0..MAX_AUTO_BACKUPS + 2 {
+            create_auto_backup(&connection, &data_dir).unwrap();
+            // The file names only go down to milliseconds.
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        assert_eq!(get_auto_backups(&data_dir).len(), MAX_AUTO_BACKUPS);
+
+        let _ = std::fs::remove_dir_all(&data_dir.path);
+    }
+}
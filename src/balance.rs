@@ -0,0 +1,151 @@
+use chrono::{DateTime, Duration, Local};
+
+use crate::{
+    skills::{SkillHashtags, SkillTypes},
+    workout::TimedWorkout,
+};
+
+// A muscle group counts as neglected if it got less than this share of the average reps.
+pub const NEGLECTED_SHARE: f64 = 0.5;
+
+/// The time span the balance is calculated for, counting back from now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl Period {
+    #[must_use]
+    /// Gets the time the period starts at, or `None` if it includes every workout.
+    pub fn get_start(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            Period::Week => Some(now - Duration::days(7)),
+            Period::Month => Some(now - Duration::days(30)),
+            Period::Year => Some(now - Duration::days(365)),
+            Period::All => None,
+        }
+    }
+}
+
+/// How the reps of a period are spread over the muscle groups and skill types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Balance {
+    // Every hashtag, even the ones without any reps.
+    pub hashtags: Vec<(SkillHashtags, usize)>,
+    pub skill_types: Vec<(SkillTypes, usize)>,
+}
+
+impl Balance {
+    #[must_use]
+    /// Gets the hashtags that got a lot less reps than the average one, the least trained first.
+    pub fn get_neglected(&self) -> Vec<SkillHashtags> {
+        let average = self.hashtags.iter().map(|(_, reps)| *reps).sum::<usize>() as f64
+            / self.hashtags.len().max(1) as f64;
+
+        let mut neglected: Vec<&(SkillHashtags, usize)> = self
+            .hashtags
+            .iter()
+            .filter(|(_, reps)| (*reps as f64) < average * NEGLECTED_SHARE)
+            .collect();
+        neglected.sort_by_key(|(_, reps)| *reps);
+
+        neglected
+            .into_iter()
+            .map(|(hashtag, _)| hashtag.clone())
+            .collect()
+    }
+}
+
+#[must_use]
+/// Adds up the reps of the workouts since `since` for every hashtag and skill type.
+/// A skill trains all of its hashtags, so its reps count fully for each of them.
+pub fn get_balance(workouts: &[TimedWorkout], since: Option<DateTime<Local>>) -> Balance {
+    let mut balance = Balance {
+        hashtags: SkillHashtags::get_all_hashtags()
+            .into_iter()
+            .filter(|h| *h != SkillHashtags::Empty)
+            .map(|h| (h, 0))
+            .collect(),
+        skill_types: vec![
+            (SkillTypes::Arms, 0),
+            (SkillTypes::Core, 0),
+            (SkillTypes::Legs, 0),
+            (SkillTypes::Yoga, 0),
+        ],
+    };
+
+    for (_, workout) in workouts
+        .iter()
+        .filter(|(time, _)| since.is_none_or(|since| *time >= since))
+    {
+        for (skill, reps) in &workout.skill {
+            for (hashtag, hashtag_reps) in balance.hashtags.iter_mut() {
+                if skill.hashtags.contains(hashtag) {
+                    *hashtag_reps += reps;
+                }
+            }
+            for (skill_type, type_reps) in balance.skill_types.iter_mut() {
+                if skill.skill_type == *skill_type {
+                    *type_reps += reps;
+                }
+            }
+        }
+    }
+
+    balance
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{skills::all_skills_default, workout::Workout};
+
+    #[test]
+    fn test_balance() {
+        let skills = all_skills_default();
+        let squat = skills.iter().find(|s| s.name == "Squat").unwrap();
+        let now = Local::now();
+
+        let workouts = vec![
+            (
+                now,
+                Workout {
+                    id: 2,
+                    skill: vec![(squat.clone(), 100)],
+                },
+            ),
+            (
+                now - Duration::days(10),
+                Workout {
+                    id: 1,
+                    skill: vec![(squat.clone(), 50)],
+                },
+            ),
+        ];
+
+        let balance = get_balance(&workouts, Period::Week.get_start(now));
+        for hashtag in squat
+            .hashtags
+            .iter()
+            .filter(|h| **h != SkillHashtags::Empty)
+        {
+            assert!(balance.hashtags.contains(&(hashtag.clone(), 100)));
+        }
+        assert!(balance
+            .skill_types
+            .contains(&(squat.skill_type.clone(), 100)));
+
+        let balance = get_balance(&workouts, Period::All.get_start(now));
+        assert!(balance
+            .skill_types
+            .contains(&(squat.skill_type.clone(), 150)));
+
+        // Only the hashtags of the squat got trained, everything else is neglected.
+        let neglected = balance.get_neglected();
+        assert!(neglected.contains(&SkillHashtags::Chest));
+        assert!(!neglected.contains(&squat.hashtags[0]));
+    }
+}
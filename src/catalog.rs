@@ -0,0 +1,224 @@
+use std::{collections::HashSet, error::Error};
+
+use rusqlite::Connection;
+use serde::Deserialize;
+
+use crate::{
+    goals::DOUBLE_PROFILE,
+    skills::{Skill, SkillHashtags},
+};
+
+/// Every skill in the game with its stats, as found in assets/skills.json.
+/// The version goes up whenever the game data in the file changes,
+/// so that existing databases know they have to be updated.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillCatalog {
+    pub version: usize,
+    pub skills: Vec<Skill>,
+}
+
+/// Parses and checks a skill catalog.
+/// The types of the skills and hashtags are checked while parsing, everything else afterwards.
+pub fn parse_catalog(file_content: &str) -> Result<SkillCatalog, Box<dyn Error>> {
+    let catalog: SkillCatalog = serde_json::from_str(file_content)?;
+
+    let mut names = HashSet::new();
+    for skill in &catalog.skills {
+        if skill.name.trim().is_empty() {
+            return Err("The skill catalog contains a skill without a name.".into());
+        }
+        if !names.insert(skill.name.to_ascii_lowercase()) {
+            return Err(format!("The skill catalog contains \"{}\" twice.", skill.name).into());
+        }
+        if skill.hashtags.iter().all(|h| *h == SkillHashtags::Empty) {
+            return Err(format!("The skill \"{}\" has no hashtags.", skill.name).into());
+        }
+        if skill.goal_reps == 0 {
+            return Err(format!("The skill \"{}\" has no goal.", skill.name).into());
+        }
+        if skill.completed_reps > 0 || skill.custom {
+            return Err(format!(
+                "The skill \"{}\" has progress or is marked as custom, the catalog only has game data.",
+                skill.name
+            )
+            .into());
+        }
+    }
+
+    Ok(catalog)
+}
+
+/// Gets the skill catalog bundled with the program.
+pub fn get_bundled_catalog() -> Result<SkillCatalog, Box<dyn Error>> {
+    parse_catalog(include_str!("../assets/skills.json"))
+}
+
+/// Gets the version of the last skill catalog merged into the database.
+pub fn get_catalog_version(connection: &Connection) -> Result<usize, Box<dyn Error>> {
+    let version = connection.query_row("SELECT version FROM skill_catalog", [], |r| r.get(0))?;
+
+    Ok(version)
+}
+
+/// Writes the game data of a newer catalog into the database, and adds the skills that are new in it.
+/// Only the stats of the skills change, the reps and goals of the user stay how they are.
+/// Returns whether the catalog was newer.
+pub fn merge_catalog(
+    connection: &Connection,
+    catalog: &SkillCatalog,
+) -> Result<bool, Box<dyn Error>> {
+    if catalog.version <= get_catalog_version(connection)? {
+        return Ok(false);
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for skill in &catalog.skills {
+        let changed = transaction.execute(
+            "UPDATE skills SET type = :type, hits = :hits WHERE name = :name AND custom = 0",
+            (&skill.skill_type, &skill.hits, &skill.name),
+        )?;
+
+        // A custom skill with the same name stays as it is, even if only the case differs.
+        if changed > 0 {
+            skill.save_stats(&transaction)?;
+        } else {
+            let added = transaction.execute(
+                "
+                    INSERT INTO skills (name, type, hits, goal_reps, completed_reps, custom)
+                    SELECT ?1, ?2, ?3, ?4, 0, 0
+                    WHERE NOT EXISTS (SELECT 1 FROM skills WHERE name = ?1 COLLATE NOCASE)
+                ",
+                (&skill.name, &skill.skill_type, &skill.hits, skill.goal_reps),
+            )?;
+            if added > 0 {
+                skill.save_stats(&transaction)?;
+                // The 2x Challenge has twice the goal of the game, every other profile gets that goal.
+                transaction.execute(
+                    "
+                        INSERT INTO goal_profiles
+                        SELECT DISTINCT name, ?1, CASE name WHEN ?3 THEN ?2 * 2 ELSE ?2 END FROM goal_profiles
+                    ",
+                    (&skill.name, skill.goal_reps, DOUBLE_PROFILE),
+                )?;
+            }
+        }
+    }
+
+    transaction.execute("UPDATE skill_catalog SET version = ?", [catalog.version])?;
+
+    transaction.commit()?;
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        custom::add_custom_skill, db::test::test_connection, goals::TITLE_PROFILE,
+        skills::SkillTypes,
+    };
+
+    #[test]
+    fn test_bundled_catalog() {
+        let catalog = get_bundled_catalog().unwrap();
+
+        assert!(catalog.version > 0);
+        assert!(catalog
+            .skills
+            .iter()
+            .any(|s| s.name == "Squat" && s.hashtags[0] == SkillHashtags::Legs));
+    }
+
+    #[test]
+    fn test_invalid_catalog() {
+        let skill = r#"{ "name": "Squat", "skill_type": "Legs", "hits": "Three", "damage": [30, 350, 460, 745],
+            "unlocks": [1, 120, 195, 286], "hashtags": ["Legs", "Empty", "Empty"], "recharge_time": [1, 2, 3, 0], "goal_reps": 3000 }"#;
+
+        assert!(parse_catalog(&format!(r#"{{ "version": 1, "skills": [{}] }}"#, skill)).is_ok());
+        assert!(parse_catalog(&format!(
+            r#"{{ "version": 1, "skills": [{0}, {0}] }}"#,
+            skill
+        ))
+        .is_err());
+        assert!(parse_catalog(&format!(
+            r#"{{ "version": 1, "skills": [{}] }}"#,
+            skill.replace("\"Legs\", \"Empty\"", "\"Thighs\", \"Empty\"")
+        ))
+        .is_err());
+        assert!(parse_catalog(&format!(
+            r#"{{ "version": 1, "skills": [{}] }}"#,
+            skill.replace(
+                "[\"Legs\", \"Empty\", \"Empty\"]",
+                "[\"Empty\", \"Empty\", \"Empty\"]"
+            )
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge_catalog() {
+        let connection = test_connection();
+        let mut catalog = get_bundled_catalog().unwrap();
+
+        // The bundled catalog is merged already.
+        assert!(!merge_catalog(&connection, &catalog).unwrap());
+
+        connection
+            .execute(
+                "UPDATE skills SET goal_reps = 500, completed_reps = 120 WHERE name = 'Squat'",
+                (),
+            )
+            .unwrap();
+        connection
+            .execute(
+                "UPDATE skill_levels SET damage = 1 WHERE skill = 'Squat'",
+                (),
+            )
+            .unwrap();
+        catalog.version += 1;
+        catalog.skills.push(Skill {
+            name: "New Skill".into(),
+            ..catalog.skills[0].clone()
+        });
+        // A custom skill that only differs in case is not replaced or added twice.
+        add_custom_skill(
+            &connection,
+            &Skill::new_custom(
+                "pushups",
+                SkillTypes::Arms,
+                catalog.skills[0].hashtags.clone(),
+                None,
+            ),
+        )
+        .unwrap();
+        catalog.skills.push(Skill {
+            name: "Pushups".into(),
+            ..catalog.skills[0].clone()
+        });
+        assert!(merge_catalog(&connection, &catalog).unwrap());
+
+        let skills = Skill::get_all_skills(&connection);
+        let squat = skills.iter().find(|s| s.name == "Squat").unwrap();
+        let catalog_squat = catalog.skills.iter().find(|s| s.name == "Squat").unwrap();
+        assert_eq!(squat.damage, catalog_squat.damage);
+        assert_eq!(squat.goal_reps, 500);
+        assert_eq!(squat.completed_reps, 120);
+        assert!(skills.iter().any(|s| s.name == "New Skill"));
+        assert!(!skills.iter().any(|s| s.name == "Pushups"));
+        // New skills get the goal of the catalog in every profile, and twice that in the 2x Challenge.
+        let goal_of = |profile: &str| -> usize {
+            connection
+                .query_row(
+                    "SELECT goal_reps FROM goal_profiles WHERE name = ? AND skill = 'New Skill'",
+                    [profile],
+                    |r| r.get(0),
+                )
+                .unwrap()
+        };
+        assert_eq!(goal_of(TITLE_PROFILE), catalog.skills[0].goal_reps);
+        assert_eq!(goal_of(DOUBLE_PROFILE), catalog.skills[0].goal_reps * 2);
+        assert_eq!(get_catalog_version(&connection).unwrap(), catalog.version);
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+
+use crate::{
+    skills::{Skill, SkillTypes},
+    workout::HistoryEntry,
+};
+
+/// How the history gets grouped together in the charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Week,
+    Month,
+}
+
+impl Bucket {
+    #[must_use]
+    /// Gets the first day of the bucket a date falls into, weeks start on monday.
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Day => date,
+            Bucket::Week => date - Duration::days(date.weekday().num_days_from_monday().into()),
+            Bucket::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// The skills whose reps get counted in a chart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChartSelection {
+    All,
+    SkillType(SkillTypes),
+    // The name of the skill.
+    Skill(String),
+}
+
+impl ChartSelection {
+    #[must_use]
+    pub fn contains(&self, skill: &Skill) -> bool {
+        match self {
+            ChartSelection::All => true,
+            ChartSelection::SkillType(skill_type) => skill.skill_type == *skill_type,
+            ChartSelection::Skill(name) => skill.name == *name,
+        }
+    }
+}
+
+/// The progress at the end of a bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressPoint {
+    // The first day of the bucket.
+    pub date: NaiveDate,
+    // All reps done for the selected skills up to the end of the bucket.
+    pub reps: i64,
+    // The title progress of all skills at the end of the bucket, the same as the total in `view_progess`.
+    pub title_percent: f64,
+}
+
+/// Adds up the reps from the history to get the progress over time.
+/// Manual adjustments count as the difference they made, so the last point matches the current totals.
+/// Only buckets with something in the history get a point.
+pub fn get_progress_over_time(
+    history: &[(DateTime<Local>, HistoryEntry)],
+    skills: &[Skill],
+    selection: &ChartSelection,
+    bucket: Bucket,
+) -> Vec<ProgressPoint> {
+    let mut entries: Vec<&(DateTime<Local>, HistoryEntry)> = history.iter().collect();
+    entries.sort_by_key(|(time, _)| *time);
+
+    let all_goal = skills.iter().map(|s| s.goal_reps).sum::<usize>() as f64;
+    let mut skill_reps: HashMap<String, i64> = HashMap::new();
+    let mut points: Vec<ProgressPoint> = Vec::new();
+
+    for (time, entry) in entries {
+        let changes: Vec<(&Skill, i64)> = match entry {
+            HistoryEntry::Workout(workout) => workout
+                .skill
+                .iter()
+                .map(|(skill, reps)| (skill, *reps as i64))
+                .collect(),
+            HistoryEntry::Adjustments(adjustments) => adjustments
+                .iter()
+                .map(|a| (&a.skill, a.new_reps - a.old_reps))
+                .collect(),
+        };
+
+        for (skill, reps) in changes {
+            *skill_reps.entry(skill.name.clone()).or_insert(0) += reps;
+        }
+
+        let point = ProgressPoint {
+            date: bucket.start_of(time.naive_local().date()),
+            reps: skills
+                .iter()
+                .filter(|s| selection.contains(s))
+                .map(|s| skill_reps.get(&s.name).copied().unwrap_or(0))
+                .sum(),
+            title_percent: skills
+                .iter()
+                .map(|s| {
+                    let reps = skill_reps.get(&s.name).copied().unwrap_or(0).max(0) as usize;
+                    reps.min(s.goal_reps) as f64
+                })
+                .sum::<f64>()
+                / all_goal
+                * 100.0,
+        };
+
+        // Later entries in the same bucket replace the earlier ones.
+        match points.last_mut() {
+            Some(last) if last.date == point.date => *last = point,
+            _ => points.push(point),
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::test::test_connection,
+        workout::{get_history_from_db, log_workout_to_db, save_manual_reps_to_db},
+    };
+
+    #[test]
+    fn test_buckets() {
+        let date = NaiveDate::from_ymd(2022, 10, 13);
+
+        assert_eq!(Bucket::Day.start_of(date), date);
+        assert_eq!(
+            Bucket::Week.start_of(date),
+            NaiveDate::from_ymd(2022, 10, 10)
+        );
+        assert_eq!(
+            Bucket::Month.start_of(date),
+            NaiveDate::from_ymd(2022, 10, 1)
+        );
+    }
+
+    #[test]
+    fn test_progress_over_time() {
+        let connection = test_connection();
+        let skills = Skill::get_all_skills(&connection);
+
+        let mut rep_list = vec![String::new(); skills.len()];
+        rep_list[0] = "100".into();
+        rep_list[1] = "50".into();
+        log_workout_to_db(&connection, &skills, &rep_list).unwrap();
+
+        let skills = Skill::get_all_skills(&connection);
+        let mut rep_list = vec![String::new(); skills.len()];
+        rep_list[0] = "40".into();
+        save_manual_reps_to_db(&connection, &skills, &rep_list).unwrap();
+
+        let skills = Skill::get_all_skills(&connection);
+        let history = get_history_from_db(&connection);
+
+        let points = get_progress_over_time(&history, &skills, &ChartSelection::All, Bucket::Day);
+        assert_eq!(points.last().unwrap().reps, 90);
+
+        let points = get_progress_over_time(
+            &history,
+            &skills,
+            &ChartSelection::Skill(skills[1].name.clone()),
+            Bucket::Month,
+        );
+        let last = points.last().unwrap();
+        assert_eq!(last.reps, 50);
+
+        let all_goal = skills.iter().map(|s| s.goal_reps).sum::<usize>() as f64;
+        assert!((last.title_percent - 90.0 / all_goal * 100.0).abs() < 1e-9);
+    }
+}
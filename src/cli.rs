@@ -0,0 +1,600 @@
+use std::{
+    error::Error,
+    fs::{read_to_string, File},
+    io::Write,
+};
+
+use chrono::{Datelike, Duration, Local, Timelike};
+use rusqlite::Connection;
+
+use crate::{
+    backup::{
+        create_auto_backup, create_backup, diff_backup, read_backup, restore_backup, write_backup,
+    },
+    csv::{
+        export_progress, export_workouts, import_workouts, parse_workouts_csv, preview_import,
+        ImportMode,
+    },
+    forecast::{forecast_all, forecast_skill, get_recent_reps, Forecast},
+    lang::check_all_translations,
+    paths::{DataDir, DATA_DIR_FLAG},
+    settings::load_settings,
+    skills::Skill,
+    stats::{get_stats, get_training_day},
+    workout::{
+        get_history_from_db, get_workouts_from_db, log_workout_to_db, save_manual_reps_to_db,
+        HistoryEntry,
+    },
+};
+
+pub const USAGE: &str = "Usage: rfa-tracker [--data-dir <folder>] [command]
+
+Starts the window if no command is given.
+
+Commands:
+    log --skill <name> <reps> ...        Logs a workout with the reps for every skill given
+    progress                             Shows the progress for every skill
+    history [--limit <amount>]           Shows the previous workouts, the newest first
+    stats                                Shows the workout streaks and other statistics
+    set-reps --skill <name> <reps> ...   Sets the total reps of every skill given
+    export-workouts <file>               Exports every workout into a csv file, use - for the terminal
+    export-progress <file>               Exports the progress of every skill into a csv file
+    import <file> [--replace] [--dry-run]
+                                         Imports workouts from a csv file, merging them into the history
+                                         or replacing it, --dry-run only shows what would change
+    backup <file>                        Saves the settings, progress and history into a json file
+    restore <file> [--dry-run]           Replaces everything with a backup, --dry-run only shows what would change
+    check-translations                   Checks the bundled translations and the files in the languages folder
+                                         for missing, unknown and unused keys
+    help                                 Shows this message";
+
+/// A command given on the command line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Log(Vec<(String, usize)>),
+    Progress,
+    History(Option<usize>),
+    Stats,
+    SetReps(Vec<(String, usize)>),
+    ExportWorkouts(String),
+    ExportProgress(String),
+    Import {
+        path: String,
+        mode: ImportMode,
+        dry_run: bool,
+    },
+    Backup(String),
+    Restore {
+        path: String,
+        dry_run: bool,
+    },
+    CheckTranslations,
+    Help,
+}
+
+/// Parses the command line arguments, including the name of the program.
+/// Returns `None` if there is no command, in which case the window should start.
+pub fn parse_args(args: &[String]) -> Result<Option<Command>, String> {
+    // The data directory is handled somewhere else, so we skip over it here.
+    let mut rest = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == DATA_DIR_FLAG {
+            iter.next();
+        } else if !arg.starts_with(&format!("{}=", DATA_DIR_FLAG)) {
+            rest.push(arg.as_str());
+        }
+    }
+
+    let (command, options) = match rest.split_first() {
+        Some((command, options)) => (*command, options),
+        None => return Ok(None),
+    };
+
+    match command {
+        "log" => Ok(Some(Command::Log(parse_skill_reps(options)?))),
+        "set-reps" => Ok(Some(Command::SetReps(parse_skill_reps(options)?))),
+        "progress" => match options {
+            [] => Ok(Some(Command::Progress)),
+            _ => Err(format!("Unexpected argument: {}", options[0])),
+        },
+        "stats" => match options {
+            [] => Ok(Some(Command::Stats)),
+            _ => Err(format!("Unexpected argument: {}", options[0])),
+        },
+        "history" => match options {
+            [] => Ok(Some(Command::History(None))),
+            ["--limit", limit] => Ok(Some(Command::History(Some(
+                limit
+                    .parse()
+                    .map_err(|_| format!("Invalid limit: {}", limit))?,
+            )))),
+            _ => Err(format!("Unexpected argument: {}", options[0])),
+        },
+        "export-workouts" => match options {
+            [path] => Ok(Some(Command::ExportWorkouts((*path).into()))),
+            _ => Err("Expected export-workouts <file>".into()),
+        },
+        "export-progress" => match options {
+            [path] => Ok(Some(Command::ExportProgress((*path).into()))),
+            _ => Err("Expected export-progress <file>".into()),
+        },
+        "import" => match options.split_first() {
+            Some((path, flags)) if flags.iter().all(|f| ["--replace", "--dry-run"].contains(f)) => {
+                Ok(Some(Command::Import {
+                    path: (*path).into(),
+                    mode: if flags.contains(&"--replace") {
+                        ImportMode::Replace
+                    } else {
+                        ImportMode::Merge
+                    },
+                    dry_run: flags.contains(&"--dry-run"),
+                }))
+            }
+            _ => Err("Expected import <file> [--replace] [--dry-run]".into()),
+        },
+        "backup" => match options {
+            [path] => Ok(Some(Command::Backup((*path).into()))),
+            _ => Err("Expected backup <file>".into()),
+        },
+        "restore" => match options {
+            [path] => Ok(Some(Command::Restore {
+                path: (*path).into(),
+                dry_run: false,
+            })),
+            [path, "--dry-run"] => Ok(Some(Command::Restore {
+                path: (*path).into(),
+                dry_run: true,
+            })),
+            _ => Err("Expected restore <file> [--dry-run]".into()),
+        },
+        "check-translations" => match options {
+            [] => Ok(Some(Command::CheckTranslations)),
+            _ => Err(format!("Unexpected argument: {}", options[0])),
+        },
+        "help" | "--help" | "-h" => Ok(Some(Command::Help)),
+        _ => Err(format!("Unknown command: {}", command)),
+    }
+}
+
+/// Parses a list of `--skill <name> <reps>` arguments.
+fn parse_skill_reps(options: &[&str]) -> Result<Vec<(String, usize)>, String> {
+    let mut skill_reps = Vec::new();
+
+    for chunk in options.chunks(3) {
+        match chunk {
+            ["--skill", name, reps] => skill_reps.push((
+                (*name).to_owned(),
+                reps.parse()
+                    .map_err(|_| format!("Invalid reps for {}: {}", name, reps))?,
+            )),
+            _ => {
+                return Err(format!(
+                    "Expected --skill <name> <reps>, got: {}",
+                    chunk.join(" ")
+                ))
+            }
+        }
+    }
+
+    if skill_reps.is_empty() {
+        return Err("Expected at least one --skill <name> <reps>".into());
+    }
+
+    Ok(skill_reps)
+}
+
+/// Turns a list of skill names and reps into the list of reps for every skill,
+/// the same way they would be entered in the window.
+fn get_rep_list(skills: &[Skill], skill_reps: &[(String, usize)]) -> Result<Vec<String>, String> {
+    let mut rep_list = vec![String::new(); skills.len()];
+
+    for (name, reps) in skill_reps {
+        let i = skills
+            .iter()
+            .position(|s| s.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("The skill \"{}\" does not exist.", name))?;
+
+        // The same skill given twice gets added up.
+        let previous = rep_list[i].parse::<usize>().unwrap_or(0);
+        rep_list[i] = (previous + reps).to_string();
+    }
+
+    Ok(rep_list)
+}
+
+/// Formats a forecast to fit into the progress table.
+fn format_forecast(forecast: Forecast) -> String {
+    match forecast {
+        Forecast::Done => "Done".into(),
+        Forecast::Stalled => "Stalled".into(),
+        Forecast::Date(date) => date.format("%Y/%m/%d").to_string(),
+    }
+}
+
+/// Runs a command and writes the output into `out`.
+/// Commands that overwrite data save an automatic backup into the data directory first.
+pub fn run_command(
+    connection: &Connection,
+    data_dir: &DataDir,
+    command: &Command,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    let skills = Skill::get_all_skills(connection);
+
+    match command {
+        Command::Log(skill_reps) => {
+            let rep_list = get_rep_list(&skills, skill_reps)?;
+            log_workout_to_db(connection, &skills, &rep_list)?;
+
+            for (skill, reps) in skills.iter().zip(rep_list.iter()) {
+                if !reps.is_empty() {
+                    writeln!(
+                        out,
+                        "{}: +{} ({} total)",
+                        skill.name,
+                        reps,
+                        skill.completed_reps + reps.parse::<usize>().unwrap_or(0)
+                    )?;
+                }
+            }
+        }
+        Command::SetReps(skill_reps) => {
+            let rep_list = get_rep_list(&skills, skill_reps)?;
+            create_auto_backup(connection, data_dir)?;
+            save_manual_reps_to_db(connection, &skills, &rep_list)?;
+
+            for (skill, reps) in skills.iter().zip(rep_list.iter()) {
+                if !reps.is_empty() {
+                    writeln!(out, "{}: {} -> {}", skill.name, skill.completed_reps, reps)?;
+                }
+            }
+        }
+        Command::Progress => {
+            let forecast_days = load_settings(data_dir)?.forecast_days;
+            let recent_reps =
+                get_recent_reps(connection, Local::now() - Duration::days(forecast_days))?;
+            let today = Local::now().naive_local().date();
+
+            let width = skills.iter().map(|s| s.name.len()).max().unwrap_or(0);
+
+            writeln!(
+                out,
+                "{:width$}  {:>8}  {:>8}  {:>8}  {:>10}",
+                "Skill",
+                "Reps",
+                "To do",
+                "Progress",
+                "Done by",
+                width = width
+            )?;
+            for skill in &skills {
+                writeln!(
+                    out,
+                    "{:width$}  {:>8}  {:>8}  {:>7.2}%  {:>10}",
+                    skill.name,
+                    skill.completed_reps,
+                    skill.get_reps_until_goal(),
+                    skill.get_rep_percent(),
+                    format_forecast(forecast_skill(skill, &recent_reps, forecast_days, today)),
+                    width = width
+                )?;
+            }
+
+            let all_sum = skills.iter().map(|s| s.completed_reps).sum::<usize>();
+            let all_goal = skills
+                .iter()
+                .map(|s| s.get_reps_until_goal())
+                .sum::<usize>();
+            let total_percent = 100.0
+                - (all_goal as f64 / skills.iter().map(|s| s.goal_reps).sum::<usize>() as f64)
+                    * 100.0;
+
+            writeln!(
+                out,
+                "{:width$}  {:>8}  {:>8}  {:>7.2}%  {:>10}",
+                "Total",
+                all_sum,
+                all_goal,
+                total_percent,
+                format_forecast(forecast_all(&skills, &recent_reps, forecast_days, today)),
+                width = width
+            )?;
+        }
+        Command::History(limit) => {
+            let history = get_history_from_db(connection);
+
+            for (time, entry) in history.iter().take(limit.unwrap_or(history.len())) {
+                writeln!(
+                    out,
+                    "{}/{:02}/{:02} - {:02}:{:02}",
+                    time.year(),
+                    time.month(),
+                    time.day(),
+                    time.hour(),
+                    time.minute()
+                )?;
+
+                match entry {
+                    HistoryEntry::Workout(workout) => {
+                        for (skill, reps) in &workout.skill {
+                            writeln!(out, "    {}: {}", skill.name, reps)?;
+                        }
+                    }
+                    HistoryEntry::Adjustments(adjustments) => {
+                        for adjustment in adjustments {
+                            writeln!(
+                                out,
+                                "    {}: {} -> {} (manual adjustment)",
+                                adjustment.skill.name, adjustment.old_reps, adjustment.new_reps
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+        Command::Stats => {
+            let day_start_hour = load_settings(data_dir)?.day_start_hour;
+            let stats = get_stats(
+                &get_workouts_from_db(connection),
+                day_start_hour,
+                get_training_day(Local::now(), day_start_hour),
+            );
+
+            writeln!(out, "Current streak:     {} days", stats.current_streak)?;
+            writeln!(out, "Longest streak:     {} days", stats.longest_streak)?;
+            writeln!(out, "Workouts:           {}", stats.workouts)?;
+            writeln!(out, "Days with workouts: {}", stats.workout_days)?;
+            writeln!(out, "Rest days:          {}", stats.rest_days)?;
+            writeln!(out, "Workouts per week:  {:.1}", stats.workouts_per_week)?;
+            writeln!(out, "Average reps:       {:.0}", stats.average_reps)?;
+        }
+        Command::ExportWorkouts(path) => {
+            if path == "-" {
+                export_workouts(connection, out)?;
+            } else {
+                export_workouts(connection, &mut File::create(path)?)?;
+                writeln!(out, "Exported the workouts to {}", path)?;
+            }
+        }
+        Command::ExportProgress(path) => {
+            if path == "-" {
+                export_progress(connection, out)?;
+            } else {
+                export_progress(connection, &mut File::create(path)?)?;
+                writeln!(out, "Exported the progress to {}", path)?;
+            }
+        }
+        Command::Import {
+            path,
+            mode,
+            dry_run,
+        } => {
+            let workouts = parse_workouts_csv(connection, &read_to_string(path)?)?;
+            let preview = preview_import(connection, &workouts, *mode);
+
+            writeln!(
+                out,
+                "{} new workouts, {} already in the history",
+                preview.new_workouts, preview.skipped_workouts
+            )?;
+            for (skill, old_reps, new_reps) in &preview.changes {
+                writeln!(out, "{}: {} -> {}", skill.name, old_reps, new_reps)?;
+            }
+
+            if !dry_run {
+                create_auto_backup(connection, data_dir)?;
+                import_workouts(connection, &workouts, *mode)?;
+                writeln!(out, "Imported the workouts from {}", path)?;
+            }
+        }
+        Command::Backup(path) => {
+            write_backup(&create_backup(connection, data_dir)?, path.as_ref())?;
+            writeln!(out, "Saved a backup to {}", path)?;
+        }
+        Command::Restore { path, dry_run } => {
+            let backup = read_backup(connection, path.as_ref())?;
+            let diff = diff_backup(connection, data_dir, &backup)?;
+
+            writeln!(out, "Language: {} -> {}", diff.language.0, diff.language.1)?;
+            writeln!(out, "Workouts: {} -> {}", diff.workouts.0, diff.workouts.1)?;
+            writeln!(
+                out,
+                "Manual adjustments: {} -> {}",
+                diff.adjustments.0, diff.adjustments.1
+            )?;
+            for skill_diff in &diff.skills {
+                writeln!(
+                    out,
+                    "{}: {} -> {} reps, {} -> {} goal",
+                    skill_diff.skill.name,
+                    skill_diff.completed_reps.0,
+                    skill_diff.completed_reps.1,
+                    skill_diff.goal_reps.0,
+                    skill_diff.goal_reps.1
+                )?;
+            }
+            for skill in &diff.removed_skills {
+                writeln!(out, "{}: deleted", skill.name)?;
+            }
+
+            if !dry_run {
+                create_auto_backup(connection, data_dir)?;
+                restore_backup(connection, data_dir, &backup)?;
+                writeln!(out, "Restored the backup from {}", path)?;
+            }
+        }
+        Command::CheckTranslations => {
+            let report = check_all_translations(data_dir)?;
+
+            for (language, keys) in &report.missing {
+                writeln!(out, "Missing in {}: {}", language, keys.join(", "))?;
+            }
+            if !report.unknown.is_empty() {
+                writeln!(out, "Unknown keys: {}", report.unknown.join(", "))?;
+            }
+            if !report.unused.is_empty() {
+                writeln!(out, "Unused keys: {}", report.unused.join(", "))?;
+            }
+
+            if !report.is_complete() {
+                return Err("The translations are incomplete.".into());
+            }
+            writeln!(out, "Every translation is complete.")?;
+        }
+        Command::Help => writeln!(out, "{}", USAGE)?,
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        backup::{get_auto_backups, test::test_data_dir},
+        db::test::test_connection,
+    };
+
+    fn args(s: &[&str]) -> Vec<String> {
+        s.iter().map(|a| (*a).to_owned()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(&args(&["rfa"])), Ok(None));
+        assert_eq!(parse_args(&args(&["rfa", "--data-dir", "/tmp"])), Ok(None));
+        assert_eq!(
+            parse_args(&args(&[
+                "rfa",
+                "--data-dir",
+                "/tmp",
+                "log",
+                "--skill",
+                "Squat",
+                "120",
+                "--skill",
+                "Plank",
+                "30"
+            ])),
+            Ok(Some(Command::Log(vec![
+                ("Squat".into(), 120),
+                ("Plank".into(), 30)
+            ])))
+        );
+        assert_eq!(
+            parse_args(&args(&["rfa", "history", "--limit", "5"])),
+            Ok(Some(Command::History(Some(5))))
+        );
+
+        assert_eq!(
+            parse_args(&args(&["rfa", "import", "old.csv", "--dry-run"])),
+            Ok(Some(Command::Import {
+                path: "old.csv".into(),
+                mode: ImportMode::Merge,
+                dry_run: true
+            }))
+        );
+
+        assert_eq!(
+            parse_args(&args(&["rfa", "restore", "backup.json", "--dry-run"])),
+            Ok(Some(Command::Restore {
+                path: "backup.json".into(),
+                dry_run: true
+            }))
+        );
+
+        assert!(parse_args(&args(&["rfa", "log"])).is_err());
+        assert!(parse_args(&args(&["rfa", "import", "old.csv", "--force"])).is_err());
+        assert!(parse_args(&args(&["rfa", "log", "--skill", "Squat", "many"])).is_err());
+        assert!(parse_args(&args(&["rfa", "check-translations", "de"])).is_err());
+        assert!(parse_args(&args(&["rfa", "something"])).is_err());
+    }
+
+    #[test]
+    fn test_run_commands() {
+        let connection = test_connection();
+        let data_dir = test_data_dir("cli");
+        let mut out = Vec::new();
+
+        run_command(
+            &connection,
+            &data_dir,
+            &Command::Log(vec![("squat".into(), 120), ("Squat".into(), 5)]),
+            &mut out,
+        )
+        .unwrap();
+        run_command(
+            &connection,
+            &data_dir,
+            &Command::SetReps(vec![("Plank".into(), 30)]),
+            &mut out,
+        )
+        .unwrap();
+        run_command(&connection, &data_dir, &Command::History(None), &mut out).unwrap();
+        run_command(&connection, &data_dir, &Command::Progress, &mut out).unwrap();
+        run_command(&connection, &data_dir, &Command::Stats, &mut out).unwrap();
+        run_command(
+            &connection,
+            &data_dir,
+            &Command::CheckTranslations,
+            &mut out,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Squat: +125 (125 total)"));
+        assert!(output.contains("Every translation is complete."));
+        assert!(output.contains("Plank: 0 -> 30 (manual adjustment)"));
+        assert!(output.contains("Current streak:     1 days"));
+
+        // Set reps saved an automatic backup, which can be restored to undo it.
+        let backups = get_auto_backups(&data_dir);
+        assert_eq!(backups.len(), 1);
+        let path = backups[0].display().to_string();
+        let mut out = Vec::new();
+        run_command(
+            &connection,
+            &data_dir,
+            &Command::Restore {
+                path,
+                dry_run: false,
+            },
+            &mut out,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(out).unwrap();
+        assert!(output.contains("Plank: 30 -> 0 reps"));
+        let plank = Skill::get_all_skills(&connection)
+            .into_iter()
+            .find(|s| s.name == "Plank")
+            .unwrap();
+        assert_eq!(plank.completed_reps, 0);
+
+        assert!(run_command(
+            &connection,
+            &data_dir,
+            &Command::Log(vec![("Not a skill".into(), 1)]),
+            &mut Vec::new()
+        )
+        .is_err());
+
+        // A translation file of the user that is missing keys is reported.
+        std::fs::create_dir_all(data_dir.languages()).unwrap();
+        std::fs::write(
+            data_dir.languages().join("fr.json"),
+            r#"{ "back": { "fr": "Retour" } }"#,
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        assert!(run_command(
+            &connection,
+            &data_dir,
+            &Command::CheckTranslations,
+            &mut out
+        )
+        .is_err());
+        assert!(String::from_utf8(out).unwrap().contains("Missing in fr: "));
+    }
+}
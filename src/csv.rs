@@ -0,0 +1,445 @@
+use std::{collections::HashSet, error::Error, io::Write};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use rusqlite::Connection;
+
+use crate::{
+    skills::Skill,
+    workout::{get_workouts_from_db, insert_workout_to_db, TimedWorkout, Workout},
+};
+
+/// The first line of an exported workouts file.
+pub const WORKOUTS_HEADER: &str = "timestamp,skill,reps";
+/// The first line of an exported progress file.
+pub const PROGRESS_HEADER: &str = "name,type,completed,goal";
+
+/// What to do with the existing history when importing workouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Adds the imported workouts to the existing ones, skipping workouts that are already there.
+    /// A workout only counts as already there if it has exactly the same timestamp.
+    Merge,
+    /// Throws away the whole existing history and the completed reps, and uses only the imported workouts.
+    Replace,
+}
+
+/// What the database would look like after an import.
+#[derive(Debug)]
+pub struct ImportPreview {
+    pub new_workouts: usize,
+    // Workouts that are already in the database with the same time.
+    pub skipped_workouts: usize,
+    // Every skill that changes, with the completed reps before and after the import.
+    pub changes: Vec<(Skill, usize, usize)>,
+}
+
+/// Puts a field in quotes if it needs them.
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+/// Splits a csv file into its rows and the fields of each row, together with the line every row starts on.
+/// Fields in quotes can contain commas, quotes written as "" and line breaks.
+fn split_rows(input: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line_number = 1;
+    let mut row_start = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            // Windows line breaks end a row the same way.
+            ('\r', false) if chars.peek() == Some(&'\n') => (),
+            ('\n', false) => {
+                fields.push(std::mem::take(&mut field));
+                rows.push((row_start, std::mem::take(&mut fields)));
+                line_number += 1;
+                row_start = line_number;
+            }
+            ('\n', true) => {
+                field.push(c);
+                line_number += 1;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(format!("Line {}: Missing closing quote", row_start));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        rows.push((row_start, fields));
+    }
+
+    Ok(rows)
+}
+
+/// Parses a timestamp, either with a timezone like we export them, or without one in local time.
+fn parse_timestamp(s: &str) -> Option<DateTime<Local>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Some(time.with_timezone(&Local));
+    }
+
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+}
+
+/// Writes every workout into a csv file with the columns timestamp, skill and reps, the oldest workouts first.
+pub fn export_workouts(
+    connection: &Connection,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", WORKOUTS_HEADER)?;
+
+    for (time, workout) in get_workouts_from_db(connection).iter().rev() {
+        for (skill, reps) in &workout.skill {
+            writeln!(
+                out,
+                "{},{},{}",
+                time.to_rfc3339(),
+                escape_field(&skill.name),
+                reps
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the progress of every skill into a csv file with the columns name, type, completed and goal.
+pub fn export_progress(
+    connection: &Connection,
+    out: &mut impl Write,
+) -> Result<(), Box<dyn Error>> {
+    writeln!(out, "{}", PROGRESS_HEADER)?;
+
+    for skill in Skill::get_all_skills(connection) {
+        writeln!(
+            out,
+            "{},{},{},{}",
+            escape_field(&skill.name),
+            skill.skill_type,
+            skill.completed_reps,
+            skill.goal_reps
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Reads the workouts out of a csv file exported with `export_workouts`.
+/// Every skill name has to be a skill in the database, and rows with the same timestamp are put into one workout.
+/// Only rows with exactly the same timestamp count as the same workout, so two files of the same workouts
+/// need to have the times written down the same way, down to the second.
+pub fn parse_workouts_csv(
+    connection: &Connection,
+    input: &str,
+) -> Result<Vec<TimedWorkout>, Box<dyn Error>> {
+    let all_skills = Skill::get_all_skills(connection);
+
+    let mut workouts: Vec<TimedWorkout> = Vec::new();
+
+    for (i, (line_number, fields)) in split_rows(input)?.into_iter().enumerate() {
+        if fields.iter().all(|f| f.trim().is_empty())
+            || (i == 0 && fields.join(",").trim() == WORKOUTS_HEADER)
+        {
+            continue;
+        }
+
+        let (time, name, reps) = match fields.as_slice() {
+            [time, name, reps] => (time.trim(), name.trim(), reps.trim()),
+            _ => {
+                return Err(format!(
+                    "Line {}: Expected 3 columns ({}), found {}",
+                    line_number,
+                    WORKOUTS_HEADER,
+                    fields.len()
+                )
+                .into())
+            }
+        };
+
+        let time = parse_timestamp(time)
+            .ok_or_else(|| format!("Line {}: Invalid timestamp \"{}\"", line_number, time))?;
+        let skill = all_skills
+            .iter()
+            .find(|s| s.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Line {}: Unknown skill \"{}\"", line_number, name))?;
+        let reps = reps
+            .parse::<usize>()
+            .map_err(|_| format!("Line {}: Invalid reps \"{}\"", line_number, reps))?;
+
+        if reps == 0 {
+            continue;
+        }
+
+        match workouts.iter_mut().find(|(t, _)| *t == time) {
+            Some((_, workout)) => workout.skill.push((skill.clone(), reps)),
+            None => workouts.push((
+                time,
+                Workout {
+                    id: 0,
+                    skill: vec![(skill.clone(), reps)],
+                },
+            )),
+        }
+    }
+
+    workouts.sort_by_key(|(time, _)| *time);
+
+    Ok(workouts)
+}
+
+/// Gets the times of every workout already in the database.
+fn get_existing_times(connection: &Connection) -> HashSet<DateTime<Local>> {
+    get_workouts_from_db(connection)
+        .into_iter()
+        .map(|(time, _)| time)
+        .collect()
+}
+
+/// Shows what an import would change, without changing anything.
+pub fn preview_import(
+    connection: &Connection,
+    workouts: &[TimedWorkout],
+    mode: ImportMode,
+) -> ImportPreview {
+    let existing_times = match mode {
+        ImportMode::Merge => get_existing_times(connection),
+        ImportMode::Replace => HashSet::new(),
+    };
+
+    let new_workouts = workouts
+        .iter()
+        .filter(|(time, _)| !existing_times.contains(time))
+        .collect::<Vec<_>>();
+
+    let changes = Skill::get_all_skills(connection)
+        .into_iter()
+        .filter_map(|skill| {
+            let imported_reps = new_workouts
+                .iter()
+                .map(|(_, w)| w.get_reps(&skill))
+                .sum::<usize>();
+            let new_reps = match mode {
+                ImportMode::Merge => skill.completed_reps + imported_reps,
+                ImportMode::Replace => imported_reps,
+            };
+
+            if new_reps == skill.completed_reps {
+                None
+            } else {
+                let old_reps = skill.completed_reps;
+                Some((skill, old_reps, new_reps))
+            }
+        })
+        .collect();
+
+    ImportPreview {
+        new_workouts: new_workouts.len(),
+        skipped_workouts: workouts.len() - new_workouts.len(),
+        changes,
+    }
+}
+
+/// Imports the workouts into the database and updates the completed reps to match.
+/// Everything happens in one transaction, if anything goes wrong nothing is imported.
+pub fn import_workouts(
+    connection: &Connection,
+    workouts: &[TimedWorkout],
+    mode: ImportMode,
+) -> Result<(), Box<dyn Error>> {
+    let existing_times = match mode {
+        ImportMode::Merge => get_existing_times(connection),
+        ImportMode::Replace => HashSet::new(),
+    };
+
+    let transaction = connection.unchecked_transaction()?;
+
+    if mode == ImportMode::Replace {
+        transaction.execute_batch(
+            "
+                DELETE FROM workout_skills;
+                DELETE FROM workouts;
+                DELETE FROM rep_adjustments;
+                UPDATE skills SET completed_reps = 0;
+            ",
+        )?;
+    }
+
+    for (time, workout) in workouts {
+        if existing_times.contains(time) {
+            continue;
+        }
+
+        insert_workout_to_db(&transaction, *time, workout)?;
+
+        for (skill, reps) in &workout.skill {
+            skill.update_reps(&transaction, *reps)?;
+        }
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        custom::add_custom_skill,
+        db::test::test_connection,
+        skills::{SkillHashtags, SkillTypes},
+        workout::log_workout_to_db,
+    };
+
+    fn completed_reps(connection: &Connection, name: &str) -> usize {
+        Skill::get_all_skills(connection)
+            .into_iter()
+            .find(|s| s.name == name)
+            .unwrap()
+            .completed_reps
+    }
+
+    #[test]
+    fn test_split_rows() {
+        assert_eq!(
+            split_rows("a,b,c\r\n\"a, b\",\"say \"\"hi\"\"\",\n").unwrap(),
+            vec![
+                (1, vec!["a".into(), "b".into(), "c".into()]),
+                (2, vec!["a, b".into(), "say \"hi\"".into(), "".into()]),
+            ]
+        );
+        assert_eq!(
+            split_rows("\"two\nlines\",b\nc").unwrap(),
+            vec![
+                (1, vec!["two\nlines".into(), "b".into()]),
+                (3, vec!["c".into()]),
+            ]
+        );
+        assert!(split_rows("\"a,b").is_err());
+        assert_eq!(escape_field("a, \"b\""), "\"a, \"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_import_multiline_field() {
+        let connection = test_connection();
+        let name = "Rows, pulls\nand hangs";
+        let skill = Skill::new_custom(
+            name,
+            SkillTypes::Arms,
+            [
+                SkillHashtags::Back,
+                SkillHashtags::Empty,
+                SkillHashtags::Empty,
+            ],
+            None,
+        );
+        add_custom_skill(&connection, &skill).unwrap();
+        let skills = Skill::get_all_skills(&connection);
+
+        let mut reps = vec!["".to_owned(); skills.len()];
+        reps[skills.iter().position(|s| s.name == name).unwrap()] = "12".into();
+        log_workout_to_db(&connection, &skills, &reps).unwrap();
+
+        let mut out = Vec::new();
+        export_workouts(&connection, &mut out).unwrap();
+        let exported = String::from_utf8(out).unwrap();
+        assert!(exported.contains("\"Rows, pulls\nand hangs\""));
+
+        let workouts = parse_workouts_csv(&connection, &exported).unwrap();
+        assert_eq!(workouts.len(), 1);
+        assert_eq!(workouts[0].1.skill.len(), 1);
+        assert_eq!(workouts[0].1.skill[0].0.name, name);
+        assert_eq!(workouts[0].1.skill[0].1, 12);
+
+        // Line numbers count the line breaks inside quotes.
+        let broken = format!("{}{}", exported, "nope,Squat,1\n");
+        let error = parse_workouts_csv(&connection, &broken).unwrap_err();
+        assert!(error.to_string().starts_with("Line 4:"));
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let connection = test_connection();
+        let skills = Skill::get_all_skills(&connection);
+
+        let mut reps = vec!["".to_owned(); skills.len()];
+        reps[0] = "10".into();
+        reps[1] = "20".into();
+        log_workout_to_db(&connection, &skills, &reps).unwrap();
+
+        let mut out = Vec::new();
+        export_workouts(&connection, &mut out).unwrap();
+        let exported = String::from_utf8(out).unwrap();
+        assert_eq!(exported.lines().count(), 3);
+
+        let workouts = parse_workouts_csv(&connection, &exported).unwrap();
+        assert_eq!(workouts.len(), 1);
+
+        // Importing the same workouts again does nothing.
+        let preview = preview_import(&connection, &workouts, ImportMode::Merge);
+        assert_eq!(preview.new_workouts, 0);
+        assert_eq!(preview.skipped_workouts, 1);
+        assert!(preview.changes.is_empty());
+
+        let preview = preview_import(&connection, &workouts, ImportMode::Replace);
+        assert_eq!(preview.new_workouts, 1);
+        assert!(preview.changes.is_empty());
+
+        let mut out = Vec::new();
+        export_progress(&connection, &mut out).unwrap();
+        let progress = String::from_utf8(out).unwrap();
+        assert!(progress.starts_with(PROGRESS_HEADER));
+        assert!(progress.contains(&format!("{},Arms,10,", skills[0].name)));
+    }
+
+    #[test]
+    fn test_import_merge_and_replace() {
+        let connection = test_connection();
+        let skills = Skill::get_all_skills(&connection);
+
+        let mut reps = vec!["".to_owned(); skills.len()];
+        reps[skills.iter().position(|s| s.name == "Squat").unwrap()] = "50".into();
+        log_workout_to_db(&connection, &skills, &reps).unwrap();
+
+        let input = "timestamp,skill,reps
+2022-10-01 18:30:00,squat,100
+2022-10-01 18:30:00,Plank,30
+2022-10-02T09:00:00+02:00,\"Squat\",20
+";
+        let workouts = parse_workouts_csv(&connection, input).unwrap();
+        assert_eq!(workouts.len(), 2);
+
+        import_workouts(&connection, &workouts, ImportMode::Merge).unwrap();
+        assert_eq!(completed_reps(&connection, "Squat"), 170);
+        assert_eq!(completed_reps(&connection, "Plank"), 30);
+        assert_eq!(get_workouts_from_db(&connection).len(), 3);
+
+        import_workouts(&connection, &workouts, ImportMode::Replace).unwrap();
+        assert_eq!(completed_reps(&connection, "Squat"), 120);
+        assert_eq!(get_workouts_from_db(&connection).len(), 2);
+
+        assert!(parse_workouts_csv(&connection, "2022-10-01 18:30:00,Not a skill,5").is_err());
+        assert!(parse_workouts_csv(&connection, "yesterday,Squat,5").is_err());
+        assert!(parse_workouts_csv(&connection, "2022-10-01 18:30:00,Squat").is_err());
+    }
+}
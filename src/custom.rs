@@ -0,0 +1,151 @@
+use std::error::Error;
+
+use rusqlite::Connection;
+
+use crate::skills::Skill;
+
+/// Adds a custom skill to the database, and to every goal profile with its goal.
+pub fn add_custom_skill(connection: &Connection, skill: &Skill) -> Result<(), Box<dyn Error>> {
+    if skill.name.is_empty() {
+        return Err("A skill needs a name.".into());
+    }
+    if skill.goal_reps == 0 {
+        return Err("A goal has to be at least 1 rep.".into());
+    }
+    // Skills are found by their name without caring about the case, on the command line and in imports.
+    let same_name: usize = connection.query_row(
+        "SELECT COUNT(*) FROM skills WHERE name = ? COLLATE NOCASE",
+        [&skill.name],
+        |row| row.get(0),
+    )?;
+    if same_name > 0 {
+        return Err(format!("The skill \"{}\" exists already.", skill.name).into());
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+    insert_custom_skill(&transaction, skill)?;
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Writes a custom skill into the database without checking it, also used when restoring backups.
+pub fn insert_custom_skill(connection: &Connection, skill: &Skill) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "
+            INSERT INTO skills (name, type, hits, goal_reps, completed_reps, custom)
+            VALUES (:name, :type, :hits, :goal_reps, :completed_reps, 1)
+        ",
+        (
+            &skill.name,
+            &skill.skill_type,
+            &skill.hits,
+            skill.goal_reps,
+            skill.completed_reps,
+        ),
+    )?;
+    skill.save_stats(connection)?;
+    connection.execute(
+        "INSERT INTO goal_profiles SELECT DISTINCT name, ?, ? FROM goal_profiles",
+        (skill.name.clone(), skill.goal_reps),
+    )?;
+
+    Ok(())
+}
+
+/// Deletes a custom skill, which is only possible as long as it has no reps anywhere in the history.
+pub fn delete_custom_skill(connection: &Connection, skill: &Skill) -> Result<(), Box<dyn Error>> {
+    if !skill.custom {
+        return Err(format!("The skill \"{}\" is part of the game.", skill.name).into());
+    }
+
+    let history_entries: usize = connection.query_row(
+        "
+            SELECT (SELECT COUNT(*) FROM workout_skills WHERE skill = ?1)
+                + (SELECT COUNT(*) FROM rep_adjustments WHERE skill = ?1)
+        ",
+        [&skill.name],
+        |row| row.get(0),
+    )?;
+    if history_entries > 0 || skill.completed_reps > 0 {
+        return Err(format!(
+            "The skill \"{}\" has reps in the history, delete those first.",
+            skill.name
+        )
+        .into());
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+
+    transaction.execute("DELETE FROM skills WHERE name = ?", [&skill.name])?;
+    transaction.execute("DELETE FROM goal_profiles WHERE skill = ?", [&skill.name])?;
+    transaction.execute("DELETE FROM skill_hashtags WHERE skill = ?", [&skill.name])?;
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::test::test_connection,
+        goals::{get_active_profile, TITLE_PROFILE},
+        lang::Language,
+        skills::{SkillHashtags, SkillTypes, DEFAULT_CUSTOM_GOAL},
+        workout::log_workout_to_db,
+    };
+
+    #[test]
+    fn test_custom_skills() {
+        let connection = test_connection();
+
+        let pushups = Skill::new_custom(
+            " Pushups ",
+            SkillTypes::Arms,
+            [
+                SkillHashtags::Chest,
+                SkillHashtags::UpperArms,
+                SkillHashtags::Empty,
+            ],
+            None,
+        );
+        add_custom_skill(&connection, &pushups).unwrap();
+
+        let skills = Skill::get_all_skills(&connection);
+        let saved = skills.iter().find(|s| s.name == "Pushups").unwrap();
+        assert!(saved.custom);
+        assert_eq!(saved.goal_reps, DEFAULT_CUSTOM_GOAL);
+        assert_eq!(saved.hashtags, pushups.hashtags);
+        // There is no translation, so the name is shown as it is.
+        assert_eq!(
+            saved.get_translated_name(&connection, &Language("de".into())),
+            "Pushups"
+        );
+        // The profile stays active, since the new skill got the same goal in it.
+        assert_eq!(
+            get_active_profile(&connection).unwrap(),
+            Some(TITLE_PROFILE.to_owned())
+        );
+
+        let same_name =
+            Skill::new_custom("squat", SkillTypes::Legs, pushups.hashtags.clone(), None);
+        assert!(add_custom_skill(&connection, &same_name).is_err());
+        let no_goal =
+            Skill::new_custom("Jumps", SkillTypes::Legs, pushups.hashtags.clone(), Some(0));
+        assert!(add_custom_skill(&connection, &no_goal).is_err());
+
+        // Once it has reps it can't be deleted anymore.
+        let mut rep_list = vec![String::new(); skills.len()];
+        *rep_list.last_mut().unwrap() = "20".into();
+        log_workout_to_db(&connection, &skills, &rep_list).unwrap();
+        assert!(delete_custom_skill(&connection, saved).is_err());
+        assert!(delete_custom_skill(&connection, &skills[0]).is_err());
+
+        let plank = Skill::new_custom("Plank Hold", SkillTypes::Core, pushups.hashtags, Some(100));
+        add_custom_skill(&connection, &plank).unwrap();
+        delete_custom_skill(&connection, &plank).unwrap();
+        assert_eq!(Skill::get_all_skills(&connection).len(), skills.len());
+    }
+}
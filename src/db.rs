@@ -1,28 +1,120 @@
 use std::error::Error;
 
 use rusqlite::Connection;
+use serde_json::Value;
 
-use crate::{lang::get_all_translations, skills::all_skills_default};
+use crate::{
+    catalog::{get_bundled_catalog, merge_catalog, parse_catalog},
+    goals::{DOUBLE_PROFILE, TITLE_PROFILE},
+    lang::{get_all_translations, Translation},
+    paths::DataDir,
+    skills::{Skill, SkillHashtags, MAX_LEVEL},
+};
 
-/// Sets up the database for first time usage.
-/// Not really needed after starting the program for the first time.
-pub fn setup_db() -> Result<(), Box<dyn Error>> {
-    let connection = Connection::open("./db/database.db")?;
+/// A single step that changes the layout of the database.
+type Migration = fn(&Connection) -> Result<(), Box<dyn Error>>;
 
-    // First we create the translations table.
+/// Every migration, in the order they have to be applied.
+/// The schema version of a database is the amount of migrations that have already been applied to it,
+/// so new migrations must only ever be added to the end of this list.
+const MIGRATIONS: &[Migration] = &[
+    create_tables,
+    normalize_workouts,
+    create_rep_adjustments,
+    create_goal_profiles,
+    split_translations,
+    add_custom_skills,
+    create_skill_catalog,
+    normalize_skill_stats,
+];
+
+/// Opens the database and brings it up to date.
+/// This runs on every startup, so that existing databases get every schema change.
+pub fn setup_db(data_dir: &DataDir) -> Result<(), Box<dyn Error>> {
+    let connection = Connection::open(data_dir.database())?;
+
+    run_migrations(&connection)?;
+    update_translations(&connection, &get_all_translations(data_dir)?)?;
+    merge_catalog(&connection, &get_bundled_catalog()?)?;
+    // Reading every skill once, so that broken data is reported here instead of crashing a menu later.
+    Skill::try_get_all_skills(&connection)?;
+
+    Ok(())
+}
+
+/// Gets the schema version stored in the database, 0 for a new or pre-migration database.
+pub fn get_schema_version(connection: &Connection) -> Result<usize, Box<dyn Error>> {
+    let version: usize = connection.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+
+    Ok(version)
+}
+
+/// Applies every migration the database is still missing, in order.
+/// Each migration runs in its own transaction together with the version bump,
+/// so a failing migration leaves the database at the last working version.
+pub fn run_migrations(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    let version = get_schema_version(connection)?;
+
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "The database has schema version {}, but this version of the program only knows up to version {}.",
+            version,
+            MIGRATIONS.len()
+        )
+        .into());
+    }
+
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = connection.unchecked_transaction()?;
+
+        migration(&transaction)?;
+        // PRAGMA statements do not support parameters, so we have to format the version in.
+        transaction.execute_batch(&format!("PRAGMA user_version = {}", i + 1))?;
+
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the translations table with `translations`, so that new and corrected translations also reach
+/// existing databases, and languages whose files were deleted are gone.
+/// Later translations with the same key and language replace earlier ones.
+pub fn update_translations(
+    connection: &Connection,
+    translations: &[Translation],
+) -> Result<(), Box<dyn Error>> {
+    let transaction = connection.unchecked_transaction()?;
+    transaction.execute("DELETE FROM translations", ())?;
+
+    for (key, language, text) in translations {
+        transaction.execute(
+            "INSERT OR REPLACE INTO translations VALUES (:key, :lang, :text)",
+            (key, language, text),
+        )?;
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Gets the skills as they were when the first migrations were written.
+/// The migrations always have to do the same thing, so they can't use the skill catalog,
+/// which changes over time. Skills added to the catalog later are added by `merge_catalog`.
+fn get_initial_skills() -> Result<Vec<Skill>, Box<dyn Error>> {
+    Ok(parse_catalog(include_str!("../assets/migrations/skills_v1.json"))?.skills)
+}
+
+/// Migration 1: Creates the original tables and fills them with the default values.
+/// Databases created before migrations existed already have these, which is why everything here is "IF NOT EXISTS".
+fn create_tables(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    // The translations themselves are written by `update_translations` once every migration ran.
     connection.execute(
         "CREATE TABLE IF NOT EXISTS translations (key TEXT UNIQUE, en TEXT, de TEXT)",
         (),
     )?;
 
-    // And then populate it with the contents of translations.json.
-    for translation in get_all_translations()? {
-        connection.execute(
-            "INSERT OR IGNORE INTO translations VALUES (:key, :en, :de)",
-            (translation.0, translation.1, translation.2),
-        )?;
-    }
-
     connection.execute(
         "CREATE TABLE IF NOT EXISTS workouts
             (timestamp DATE, workout BLOB)",
@@ -37,7 +129,7 @@ pub fn setup_db() -> Result<(), Box<dyn Error>> {
         (),
     )?;
 
-    for skill in all_skills_default() {
+    for skill in get_initial_skills()? {
         connection.execute(
             "INSERT OR IGNORE INTO skills VALUES (:name, :type, :hits, :damage, :unlock, :hashtag, :recharge, :goal_reps, :completed_reps)",
             (
@@ -57,3 +149,373 @@ pub fn setup_db() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Migration 2: Splits up the workouts, which used to be JSON blobs of whole skills,
+/// into one row per workout and one row per skill done in that workout.
+fn normalize_workouts(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute_batch(
+        "
+            ALTER TABLE workouts RENAME TO workouts_old;
+            CREATE TABLE workouts (id INTEGER PRIMARY KEY, timestamp DATE NOT NULL);
+            CREATE TABLE workout_skills
+                (workout_id INTEGER NOT NULL REFERENCES workouts(id), skill TEXT NOT NULL, reps INTEGER NOT NULL);
+            CREATE INDEX workout_skills_workout_id ON workout_skills (workout_id);
+            INSERT INTO workouts SELECT rowid, timestamp FROM workouts_old;
+        ",
+    )?;
+
+    let mut stmt = connection.prepare("SELECT rowid, workout FROM workouts_old")?;
+    let old_workouts = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Value>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (id, workout) in old_workouts {
+        // The old workouts look like this: {"skill": [[{"name": "Squat", ...}, 120], ...]}
+        // We only read the name and reps, the rest of the skill is taken from the skills table from now on.
+        for entry in workout["skill"].as_array().unwrap_or(&Vec::new()) {
+            let name = entry[0]["name"]
+                .as_str()
+                .ok_or("Found a workout without a skill name in the database.")?;
+            let reps = entry[1]
+                .as_u64()
+                .ok_or("Found a workout with invalid reps in the database.")?;
+
+            connection.execute(
+                "INSERT INTO workout_skills VALUES (:workout_id, :skill, :reps)",
+                (id, name, reps),
+            )?;
+        }
+    }
+
+    connection.execute("DROP TABLE workouts_old", ())?;
+
+    Ok(())
+}
+
+/// Migration 3: Adds a table for manual changes to the completed reps of a skill,
+/// so that the history can explain every total, not just the ones coming from workouts.
+fn create_rep_adjustments(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "CREATE TABLE rep_adjustments
+            (id INTEGER PRIMARY KEY, timestamp DATE NOT NULL, skill TEXT NOT NULL, old_reps INTEGER NOT NULL, new_reps INTEGER NOT NULL)",
+        (),
+    )?;
+
+    Ok(())
+}
+
+/// Migration 4: Adds named goal profiles, starting with the in-game title thresholds and twice those.
+fn create_goal_profiles(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "CREATE TABLE goal_profiles
+            (name TEXT NOT NULL, skill TEXT NOT NULL, goal_reps INTEGER NOT NULL, PRIMARY KEY (name, skill))",
+        (),
+    )?;
+
+    for skill in get_initial_skills()? {
+        connection.execute(
+            "INSERT INTO goal_profiles VALUES (:name, :skill, :goal_reps)",
+            (TITLE_PROFILE, skill.name.clone(), skill.goal_reps),
+        )?;
+        connection.execute(
+            "INSERT INTO goal_profiles VALUES (:name, :skill, :goal_reps)",
+            (DOUBLE_PROFILE, skill.name.clone(), skill.goal_reps * 2),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Migration 5: Stores one row per key and language instead of one column per language,
+/// so that languages can be added without changing the table.
+fn split_translations(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute_batch(
+        "
+            ALTER TABLE translations RENAME TO translations_old;
+            CREATE TABLE translations
+                (key TEXT NOT NULL, lang TEXT NOT NULL, text TEXT NOT NULL, PRIMARY KEY (key, lang));
+            INSERT INTO translations SELECT key, 'en', en FROM translations_old WHERE key IS NOT NULL AND en IS NOT NULL;
+            INSERT INTO translations SELECT key, 'de', de FROM translations_old WHERE key IS NOT NULL AND de IS NOT NULL;
+            DROP TABLE translations_old;
+        ",
+    )?;
+
+    Ok(())
+}
+
+/// Migration 6: Marks the skills the user added themselves, so they can be told apart from the ones in the game.
+fn add_custom_skills(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "ALTER TABLE skills ADD COLUMN custom INTEGER NOT NULL DEFAULT 0",
+        (),
+    )?;
+
+    Ok(())
+}
+
+/// Migration 7: Remembers the version of the skill catalog the skills were last updated from.
+/// Starting at 0, so that the next startup brings the stats of every skill up to date.
+fn create_skill_catalog(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute_batch(
+        "
+            CREATE TABLE skill_catalog (version INTEGER NOT NULL);
+            INSERT INTO skill_catalog VALUES (0);
+        ",
+    )?;
+
+    Ok(())
+}
+
+/// Migration 8: Moves the stats of every level and the hashtags out of comma separated strings
+/// into their own tables, and drops the old columns.
+fn normalize_skill_stats(connection: &Connection) -> Result<(), Box<dyn Error>> {
+    connection.execute_batch(
+        "
+            CREATE TABLE skill_levels (skill TEXT NOT NULL, level INTEGER NOT NULL, damage INTEGER NOT NULL, unlock_level INTEGER NOT NULL, recharge INTEGER NOT NULL, PRIMARY KEY (skill, level));
+            CREATE TABLE skill_hashtags (skill TEXT NOT NULL, position INTEGER NOT NULL, hashtag TEXT NOT NULL, PRIMARY KEY (skill, position));
+        ",
+    )?;
+
+    let mut stmt =
+        connection.prepare("SELECT name, damage, unlock, hashtag, recharge, custom FROM skills")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(0)?;
+        let custom: bool = row.get(5)?;
+
+        // The strings look like "30,350,460,745,", with a comma after every value.
+        let split = |column: usize| -> Result<Vec<String>, Box<dyn Error>> {
+            let text: String = row.get(column)?;
+            Ok(text
+                .split(',')
+                .filter(|v| !v.is_empty())
+                .map(str::to_owned)
+                .collect())
+        };
+        let numbers = |column: usize, stat: &str| -> Result<Vec<usize>, Box<dyn Error>> {
+            split(column)?
+                .iter()
+                .map(|v| {
+                    v.trim().parse::<usize>().map_err(|_| {
+                        format!(
+                            "The {} of the skill \"{}\" contains \"{}\", which is not a number.",
+                            stat, name, v
+                        )
+                        .into()
+                    })
+                })
+                .collect()
+        };
+
+        // Custom skills never had real stats, so there is nothing to keep for them.
+        if !custom {
+            let damage = numbers(1, "damage")?;
+            let unlocks = numbers(2, "unlock levels")?;
+            let recharge_time = numbers(4, "recharge time")?;
+            if [&damage, &unlocks, &recharge_time]
+                .iter()
+                .any(|v| v.len() != MAX_LEVEL)
+            {
+                return Err(format!(
+                    "The skill \"{}\" does not have stats for all of its {} levels.",
+                    name, MAX_LEVEL
+                )
+                .into());
+            }
+
+            for level in 0..MAX_LEVEL {
+                connection.execute(
+                    "INSERT INTO skill_levels VALUES (:skill, :level, :damage, :unlock_level, :recharge)",
+                    (
+                        &name,
+                        level + 1,
+                        damage[level],
+                        unlocks[level],
+                        recharge_time[level],
+                    ),
+                )?;
+            }
+        }
+
+        for (i, hashtag) in split(3)?.iter().enumerate() {
+            let hashtag = hashtag.parse::<SkillHashtags>().map_err(|_| {
+                format!(
+                    "The skill \"{}\" has the unknown hashtag \"{}\".",
+                    name, hashtag
+                )
+            })?;
+            if hashtag != SkillHashtags::Empty {
+                connection.execute(
+                    "INSERT INTO skill_hashtags VALUES (:skill, :position, :hashtag)",
+                    (&name, i + 1, &hashtag),
+                )?;
+            }
+        }
+    }
+
+    connection.execute_batch(
+        "
+            ALTER TABLE skills DROP COLUMN damage;
+            ALTER TABLE skills DROP COLUMN unlock;
+            ALTER TABLE skills DROP COLUMN hashtag;
+            ALTER TABLE skills DROP COLUMN recharge;
+        ",
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    use crate::{
+        lang::{get_bundled_translations, get_string, Language},
+        skills::all_skills_default,
+        workout::get_workouts_from_db,
+    };
+
+    /// Creates a database in memory with every migration applied, the bundled translations and skill catalog.
+    pub fn test_connection() -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        run_migrations(&connection).unwrap();
+        update_translations(&connection, &get_bundled_translations().unwrap()).unwrap();
+        merge_catalog(&connection, &get_bundled_catalog().unwrap()).unwrap();
+        connection
+    }
+
+    /// Creates a database in memory the way the program did before migrations existed,
+    /// with a bit of progress and a workout in it.
+    pub fn baseline_connection() -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+
+        connection
+            .execute_batch(
+                "
+                CREATE TABLE translations (key TEXT UNIQUE, en TEXT, de TEXT);
+                CREATE TABLE workouts (timestamp DATE, workout BLOB);
+                CREATE TABLE skills (name TEXT UNIQUE, type TEXT, hits TEXT, damage TEXT, unlock TEXT, hashtag TEXT, recharge TEXT, goal_reps INTEGER, completed_reps INTEGER);
+                INSERT INTO translations VALUES ('skill_squat', 'Squat', 'Kniebeuge');
+                INSERT INTO skills VALUES ('Squat', 'Legs', 'Three', '30,350,460,745,', '1,120,195,286,', '#Legs,#Lower Body,#Glutes,', '1,2,3,0,', 3000, 120);
+                INSERT INTO workouts VALUES ('2022-10-01 16:30:00.000+00:00', '{\"skill\":[[{\"name\":\"Squat\",\"skill_type\":\"Legs\",\"hits\":\"Three\",\"damage\":[30,350,460,745],\"unlocks\":[1,120,195,286],\"hashtags\":[\"Legs\",\"LowerBody\",\"Glutes\"],\"recharge_time\":[1,2,3,0],\"goal_reps\":3000,\"completed_reps\":0},120]]}');
+                ",
+            )
+            .unwrap();
+
+        connection
+    }
+
+    #[test]
+    fn test_migrate_new_database() {
+        let connection = test_connection();
+
+        assert_eq!(get_schema_version(&connection).unwrap(), MIGRATIONS.len());
+        assert_eq!(
+            Skill::get_all_skills(&connection).len(),
+            all_skills_default().len()
+        );
+    }
+
+    #[test]
+    fn test_migrate_baseline_database() {
+        let connection = baseline_connection();
+
+        assert_eq!(get_schema_version(&connection).unwrap(), 0);
+
+        run_migrations(&connection).unwrap();
+
+        assert_eq!(get_schema_version(&connection).unwrap(), MIGRATIONS.len());
+
+        // The old translations are moved into one row per language.
+        assert_eq!(
+            get_string(&connection, &Language("de".into()), "skill_squat".into()).unwrap(),
+            "Kniebeuge"
+        );
+        update_translations(&connection, &get_bundled_translations().unwrap()).unwrap();
+
+        // The progress that was there before has to survive.
+        let skills = Skill::get_all_skills(&connection);
+        let squat = skills.iter().find(|s| s.name == "Squat").unwrap();
+        assert_eq!(squat.completed_reps, 120);
+        // The stats are moved out of the comma separated strings.
+        assert_eq!(squat.damage, [30, 350, 460, 745]);
+        assert_eq!(squat.recharge_time, [1, 2, 3, 0]);
+        assert_eq!(
+            squat.hashtags,
+            [
+                SkillHashtags::Legs,
+                SkillHashtags::LowerBody,
+                SkillHashtags::Glutes
+            ]
+        );
+        // And the skills that were missing get added.
+        assert_eq!(skills.len(), all_skills_default().len());
+
+        // The workout blob is split up into rows, keeping its timestamp.
+        let workouts = get_workouts_from_db(&connection);
+        assert_eq!(workouts.len(), 1);
+        assert_eq!(
+            workouts[0].0,
+            "2022-10-01T16:30:00+00:00"
+                .parse::<DateTime<Utc>>()
+                .unwrap()
+        );
+        assert_eq!(workouts[0].1.get_reps(squat), 120);
+
+        // Running them again does nothing.
+        run_migrations(&connection).unwrap();
+        assert_eq!(get_schema_version(&connection).unwrap(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_broken_skill_data() {
+        let connection = test_connection();
+        assert!(Skill::try_get_all_skills(&connection).is_ok());
+
+        connection
+            .execute(
+                "UPDATE skill_hashtags SET hashtag = '#Thighs' WHERE skill = 'Squat' AND position = 1",
+                (),
+            )
+            .unwrap();
+        let error = Skill::try_get_all_skills(&connection).unwrap_err();
+        assert!(error.to_string().contains("Squat"));
+        assert!(error.to_string().contains("#Thighs"));
+
+        connection
+            .execute(
+                "UPDATE skill_hashtags SET hashtag = '#Legs' WHERE skill = 'Squat' AND position = 1",
+                (),
+            )
+            .unwrap();
+        connection
+            .execute(
+                "UPDATE skill_levels SET level = 5 WHERE skill = 'Squat' AND level = 4",
+                (),
+            )
+            .unwrap();
+        assert!(Skill::try_get_all_skills(&connection).is_err());
+
+        connection
+            .execute(
+                "DELETE FROM skill_levels WHERE skill = 'Squat' AND level = 5",
+                (),
+            )
+            .unwrap();
+        assert!(Skill::try_get_all_skills(&connection).is_err());
+    }
+
+    #[test]
+    fn test_reject_newer_database() {
+        let connection = test_connection();
+
+        connection
+            .execute_batch(&format!("PRAGMA user_version = {}", MIGRATIONS.len() + 1))
+            .unwrap();
+
+        assert!(run_migrations(&connection).is_err());
+    }
+}
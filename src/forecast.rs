@@ -0,0 +1,139 @@
+use std::{collections::HashMap, error::Error};
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use rusqlite::Connection;
+
+use crate::skills::Skill;
+
+pub const DEFAULT_FORECAST_DAYS: i64 = 28;
+
+/// When a goal is expected to be reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forecast {
+    // The goal is reached already.
+    Done,
+    // Nothing was done recently, so the goal is never reached at this rate.
+    Stalled,
+    Date(NaiveDate),
+}
+
+/// Gets the reps done for every skill in the workouts since the given time.
+/// Manual adjustments are left out, as they are no training.
+pub fn get_recent_reps(
+    connection: &Connection,
+    since: DateTime<Local>,
+) -> Result<HashMap<String, i64>, Box<dyn Error>> {
+    let mut stmt = connection.prepare(
+        "
+            SELECT workout_skills.skill, SUM(workout_skills.reps)
+            FROM workout_skills
+            JOIN workouts ON workouts.id = workout_skills.workout_id
+            WHERE julianday(workouts.timestamp) >= julianday(?)
+            GROUP BY workout_skills.skill
+        ",
+    )?;
+
+    let recent_reps = stmt
+        .query_map([since], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<String, i64>, _>>()?;
+
+    Ok(recent_reps)
+}
+
+#[must_use]
+/// Estimates when a skill reaches its goal, if it keeps being trained at the rate of the last `window_days`.
+pub fn forecast_skill(
+    skill: &Skill,
+    recent_reps: &HashMap<String, i64>,
+    window_days: i64,
+    today: NaiveDate,
+) -> Forecast {
+    let remaining = skill.get_reps_until_goal();
+    if remaining == 0 {
+        return Forecast::Done;
+    }
+
+    let reps = recent_reps.get(&skill.name).copied().unwrap_or(0);
+    if reps <= 0 || window_days <= 0 {
+        return Forecast::Stalled;
+    }
+
+    let reps_per_day = reps as f64 / window_days as f64;
+    let days = (remaining as f64 / reps_per_day).ceil();
+
+    // Far enough in the future to overflow the date is as good as never.
+    match today.checked_add_signed(Duration::days(days.min(i32::MAX as f64) as i64)) {
+        Some(date) => Forecast::Date(date),
+        None => Forecast::Stalled,
+    }
+}
+
+#[must_use]
+/// Estimates when every skill reaches its goal, which is when the slowest one does.
+pub fn forecast_all(
+    skills: &[Skill],
+    recent_reps: &HashMap<String, i64>,
+    window_days: i64,
+    today: NaiveDate,
+) -> Forecast {
+    skills
+        .iter()
+        .map(|s| forecast_skill(s, recent_reps, window_days, today))
+        .fold(Forecast::Done, |last, forecast| match (last, forecast) {
+            (Forecast::Stalled, _) | (_, Forecast::Stalled) => Forecast::Stalled,
+            (Forecast::Done, other) | (other, Forecast::Done) => other,
+            (Forecast::Date(a), Forecast::Date(b)) => Forecast::Date(a.max(b)),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{db::test::test_connection, workout::log_workout_to_db};
+
+    #[test]
+    fn test_forecast() {
+        let connection = test_connection();
+        let skills = Skill::get_all_skills(&connection);
+        let today = NaiveDate::from_ymd(2022, 10, 1);
+
+        let mut rep_list = vec![String::new(); skills.len()];
+        rep_list[0] = "280".into();
+        log_workout_to_db(&connection, &skills, &rep_list).unwrap();
+
+        let recent_reps = get_recent_reps(&connection, Local::now() - Duration::days(28)).unwrap();
+        assert_eq!(recent_reps.get(&skills[0].name), Some(&280));
+
+        // Nothing happened since the workout.
+        let later_reps = get_recent_reps(&connection, Local::now() + Duration::days(1)).unwrap();
+        assert!(later_reps.is_empty());
+
+        let mut skill = Skill::get_all_skills(&connection).remove(0);
+        skill.goal_reps = 1280;
+
+        // 280 reps in 28 days is 10 a day, so the remaining 1000 take 100 days.
+        assert_eq!(
+            forecast_skill(&skill, &recent_reps, 28, today),
+            Forecast::Date(today + Duration::days(100))
+        );
+        assert_eq!(
+            forecast_skill(&skills[1], &recent_reps, 28, today),
+            Forecast::Stalled
+        );
+
+        skill.goal_reps = 280;
+        assert_eq!(
+            forecast_skill(&skill, &recent_reps, 28, today),
+            Forecast::Done
+        );
+
+        assert_eq!(
+            forecast_all(&[skill.clone()], &recent_reps, 28, today),
+            Forecast::Done
+        );
+        assert_eq!(
+            forecast_all(&[skill, skills[1].clone()], &recent_reps, 28, today),
+            Forecast::Stalled
+        );
+    }
+}
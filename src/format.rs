@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, NaiveDate, TimeZone,
+};
+
+// The keys in the translations that tell us how a language writes dates and numbers.
+pub const DATE_FORMAT_KEY: &str = "format_date";
+pub const MONTH_FORMAT_KEY: &str = "format_month";
+pub const TIME_FORMAT_KEY: &str = "format_time";
+pub const THOUSANDS_SEPARATOR_KEY: &str = "format_thousands_separator";
+pub const DECIMAL_SEPARATOR_KEY: &str = "format_decimal_separator";
+pub const PERCENT_FORMAT_KEY: &str = "format_percent";
+
+// Shown instead of numbers that are not a number or infinite, like a percent of a goal of 0.
+pub const INVALID_NUMBER: &str = "—";
+
+/// How dates, times and numbers are written in a language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale {
+    // Formats in the style of `strftime`, like "%d.%m.%Y".
+    pub date_format: String,
+    // A month without a day, like the one shown in the calendar.
+    pub month_format: String,
+    pub time_format: String,
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+    // The "{}" is replaced with the number, like "{} %".
+    pub percent_format: String,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            date_format: "%Y/%m/%d".into(),
+            month_format: "%Y/%m".into(),
+            time_format: "%H:%M".into(),
+            thousands_separator: ",".into(),
+            decimal_separator: ".".into(),
+            percent_format: "{}%".into(),
+        }
+    }
+}
+
+impl Locale {
+    #[must_use]
+    /// Gets the locale from the translated strings of a language.
+    /// Anything missing or invalid stays the same as in English.
+    pub fn from_strings(strings: &HashMap<String, String>) -> Self {
+        let mut locale = Self::default();
+
+        let is_valid_format =
+            |format: &&String| !StrftimeItems::new(format).any(|item| matches!(item, Item::Error));
+
+        if let Some(format) = strings.get(DATE_FORMAT_KEY).filter(is_valid_format) {
+            locale.date_format = format.clone();
+        }
+        if let Some(format) = strings.get(MONTH_FORMAT_KEY).filter(is_valid_format) {
+            locale.month_format = format.clone();
+        }
+        if let Some(format) = strings.get(TIME_FORMAT_KEY).filter(is_valid_format) {
+            locale.time_format = format.clone();
+        }
+        if let Some(separator) = strings.get(THOUSANDS_SEPARATOR_KEY) {
+            locale.thousands_separator = separator.clone();
+        }
+        if let Some(separator) = strings.get(DECIMAL_SEPARATOR_KEY) {
+            locale.decimal_separator = separator.clone();
+        }
+        if let Some(format) = strings.get(PERCENT_FORMAT_KEY).filter(|f| f.contains("{}")) {
+            locale.percent_format = format.clone();
+        }
+
+        locale
+    }
+
+    #[must_use]
+    /// Formats a date, like 2022/10/16.
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(&self.date_format).to_string()
+    }
+
+    #[must_use]
+    /// Formats the month of a date, like 2022/10.
+    pub fn format_month(&self, date: NaiveDate) -> String {
+        date.format(&self.month_format).to_string()
+    }
+
+    #[must_use]
+    /// Formats the time of a timestamp, like 16:30.
+    pub fn format_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        time.format(&self.time_format).to_string()
+    }
+
+    #[must_use]
+    /// Formats the date and the time of a timestamp, like the workouts in the history.
+    pub fn format_date_time<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        format!(
+            "{} - {}",
+            time.format(&self.date_format),
+            time.format(&self.time_format)
+        )
+    }
+
+    #[must_use]
+    /// Formats a whole number with a separator between every three digits, like 12,345.
+    pub fn format_number(&self, number: i64) -> String {
+        let digits = number.unsigned_abs().to_string();
+
+        let mut formatted = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                formatted.push_str(&self.thousands_separator);
+            }
+            formatted.push(digit);
+        }
+
+        if number < 0 {
+            formatted.insert(0, '-');
+        }
+
+        formatted
+    }
+
+    #[must_use]
+    /// Formats a number with a fixed amount of decimals, like 1,234.56.
+    /// Numbers that are not a number or infinite are shown as `INVALID_NUMBER`.
+    pub fn format_decimal(&self, number: f64, decimals: usize) -> String {
+        if !number.is_finite() {
+            return INVALID_NUMBER.to_owned();
+        }
+
+        let rounded = format!("{:.*}", decimals, number.abs());
+        let (whole, fraction) = rounded.split_once('.').unwrap_or((&rounded, ""));
+
+        let mut formatted = self.format_number(whole.parse().unwrap_or(0));
+        if !fraction.is_empty() {
+            formatted.push_str(&self.decimal_separator);
+            formatted.push_str(fraction);
+        }
+
+        // Checking the rounded string, so that -0.001 does not turn into -0.00.
+        if number < 0.0 && rounded.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            formatted.insert(0, '-');
+        }
+
+        formatted
+    }
+
+    #[must_use]
+    /// Formats a number that is already in percent, like 12.5 for 12.5%.
+    pub fn format_percent(&self, percent: f64, decimals: usize) -> String {
+        if !percent.is_finite() {
+            return INVALID_NUMBER.to_owned();
+        }
+
+        self.percent_format
+            .replace("{}", &self.format_decimal(percent, decimals))
+    }
+
+    #[must_use]
+    /// Formats a difference with its sign in front of it, like +1,200.
+    pub fn format_difference(&self, number: i64) -> String {
+        match number {
+            x if x > 0 => format!("+{}", self.format_number(x)),
+            x => self.format_number(x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::test::test_connection,
+        lang::{get_language_hashmaps, Language},
+    };
+    use chrono::Utc;
+
+    fn locale_of(language: &str) -> Locale {
+        let connection = test_connection();
+        let (_, _, menu_hashmap) = get_language_hashmaps(&connection, &Language(language.into()));
+
+        Locale::from_strings(&menu_hashmap)
+    }
+
+    #[test]
+    fn test_english_locale() {
+        let locale = locale_of("en");
+        assert_eq!(locale, Locale::default());
+
+        let time = "2026-10-16T07:05:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(locale.format_date(time.naive_utc().date()), "2026/10/16");
+        assert_eq!(locale.format_month(time.naive_utc().date()), "2026/10");
+        assert_eq!(locale.format_date_time(&time), "2026/10/16 - 07:05");
+        assert_eq!(locale.format_number(1_234_567), "1,234,567");
+        assert_eq!(locale.format_number(-999), "-999");
+        assert_eq!(locale.format_decimal(1234.5678, 2), "1,234.57");
+        assert_eq!(locale.format_decimal(-0.001, 2), "0.00");
+        assert_eq!(locale.format_percent(12.5, 1), "12.5%");
+        assert_eq!(locale.format_difference(1500), "+1,500");
+        assert_eq!(locale.format_difference(-20), "-20");
+        assert_eq!(locale.format_decimal(f64::NAN, 2), INVALID_NUMBER);
+        assert_eq!(locale.format_percent(f64::INFINITY, 1), INVALID_NUMBER);
+    }
+
+    #[test]
+    fn test_german_locale() {
+        let locale = locale_of("de");
+
+        let time = "2026-10-16T07:05:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(locale.format_date(time.naive_utc().date()), "16.10.2026");
+        assert_eq!(locale.format_month(time.naive_utc().date()), "10.2026");
+        assert_eq!(locale.format_date_time(&time), "16.10.2026 - 07:05");
+        assert_eq!(locale.format_number(1_234_567), "1.234.567");
+        assert_eq!(locale.format_decimal(1234.5678, 2), "1.234,57");
+        assert_eq!(locale.format_percent(99.999, 2), "100,00 %");
+        assert_eq!(locale.format_difference(-1500), "-1.500");
+    }
+
+    #[test]
+    fn test_invalid_locale() {
+        let strings = HashMap::from([
+            (DATE_FORMAT_KEY.to_owned(), "%Q".to_owned()),
+            (PERCENT_FORMAT_KEY.to_owned(), "percent".to_owned()),
+            (DECIMAL_SEPARATOR_KEY.to_owned(), ",".to_owned()),
+        ]);
+        let locale = Locale::from_strings(&strings);
+
+        assert_eq!(locale.date_format, Locale::default().date_format);
+        assert_eq!(locale.format_percent(50.0, 1), "50,0%");
+    }
+}
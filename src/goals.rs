@@ -0,0 +1,214 @@
+use std::error::Error;
+
+use rusqlite::Connection;
+
+use crate::skills::{Skill, SkillTypes};
+
+// The profiles every database starts with.
+pub const TITLE_PROFILE: &str = "Title";
+pub const DOUBLE_PROFILE: &str = "2x Challenge";
+
+#[must_use]
+/// Checks if a profile is one every database starts with, which can't be changed or deleted.
+pub fn is_built_in_profile(name: &str) -> bool {
+    [TITLE_PROFILE, DOUBLE_PROFILE]
+        .iter()
+        .any(|p| p.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Saves the goals entered for every skill, in the same format as the reps in `set_reps`.
+/// Empty or invalid inputs are skipped, and a goal has to be at least 1 rep.
+pub fn save_goals_to_db(
+    connection: &Connection,
+    skills: &[Skill],
+    goal_list: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let transaction = connection.unchecked_transaction()?;
+
+    for (skill, goal) in skills.iter().zip(goal_list.iter()) {
+        if let Ok(goal) = goal.parse::<usize>() {
+            if goal > 0 {
+                skill.set_goal(&transaction, goal)?;
+            }
+        }
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Sets the goal of every skill of a type, or of every skill if no type is given.
+pub fn set_goals_for_type(
+    connection: &Connection,
+    skill_type: Option<&SkillTypes>,
+    goal_reps: usize,
+) -> Result<(), Box<dyn Error>> {
+    if goal_reps == 0 {
+        return Err("A goal has to be at least 1 rep.".into());
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for skill in Skill::get_all_skills(&transaction)
+        .iter()
+        .filter(|s| skill_type.is_none_or(|t| s.skill_type == *t))
+    {
+        skill.set_goal(&transaction, goal_reps)?;
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Gets the names of every goal profile, sorted by name.
+pub fn get_goal_profiles(connection: &Connection) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut stmt = connection.prepare("SELECT DISTINCT name FROM goal_profiles ORDER BY name")?;
+
+    let profiles = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+
+    Ok(profiles)
+}
+
+/// Gets the profile that has exactly the current goals of every skill, if there is one.
+pub fn get_active_profile(connection: &Connection) -> Result<Option<String>, Box<dyn Error>> {
+    // A profile is active if none of its goals differ and it has a goal for every skill.
+    let profile = connection.query_row(
+        "
+            SELECT goal_profiles.name FROM goal_profiles
+            JOIN skills ON skills.name = goal_profiles.skill
+            GROUP BY goal_profiles.name
+            HAVING SUM(goal_profiles.goal_reps != skills.goal_reps) = 0
+                AND COUNT(*) = (SELECT COUNT(*) FROM skills)
+            ORDER BY goal_profiles.name
+        ",
+        [],
+        |row| row.get(0),
+    );
+
+    match profile {
+        Ok(name) => Ok(Some(name)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Saves the current goals of every skill as a profile, replacing it if it exists already.
+pub fn save_goal_profile(connection: &Connection, name: &str) -> Result<(), Box<dyn Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("A goal profile needs a name.".into());
+    }
+    if is_built_in_profile(name) {
+        return Err(format!("The goal profile \"{}\" can't be changed.", name).into());
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+
+    transaction.execute("DELETE FROM goal_profiles WHERE name = ?", [name])?;
+    transaction.execute(
+        "INSERT INTO goal_profiles SELECT ?, name, goal_reps FROM skills",
+        [name],
+    )?;
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Switches every skill to the goals saved in a profile.
+pub fn apply_goal_profile(connection: &Connection, name: &str) -> Result<(), Box<dyn Error>> {
+    let changed = connection.execute(
+        "
+            UPDATE skills SET goal_reps = (
+                SELECT goal_reps FROM goal_profiles WHERE goal_profiles.name = ?1 AND goal_profiles.skill = skills.name
+            )
+            WHERE name IN (SELECT skill FROM goal_profiles WHERE goal_profiles.name = ?1)
+        ",
+        [name],
+    )?;
+
+    if changed == 0 {
+        return Err(format!("The goal profile \"{}\" does not exist.", name).into());
+    }
+
+    Ok(())
+}
+
+/// Deletes a goal profile, the current goals stay the same.
+pub fn delete_goal_profile(connection: &Connection, name: &str) -> Result<(), Box<dyn Error>> {
+    if is_built_in_profile(name) {
+        return Err(format!("The goal profile \"{}\" can't be deleted.", name).into());
+    }
+
+    connection.execute("DELETE FROM goal_profiles WHERE name = ?", [name])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::test::test_connection;
+
+    fn goal_of(connection: &Connection, name: &str) -> usize {
+        Skill::get_all_skills(connection)
+            .into_iter()
+            .find(|s| s.name == name)
+            .unwrap()
+            .goal_reps
+    }
+
+    #[test]
+    fn test_goals() {
+        let connection = test_connection();
+        let skills = Skill::get_all_skills(&connection);
+        let title_goal = goal_of(&connection, "Squat");
+
+        assert_eq!(
+            get_active_profile(&connection).unwrap(),
+            Some(TITLE_PROFILE.to_owned())
+        );
+
+        let mut goal_list = vec![String::new(); skills.len()];
+        goal_list[0] = "0".into();
+        goal_list[1] = "1234".into();
+        save_goals_to_db(&connection, &skills, &goal_list).unwrap();
+        assert_eq!(goal_of(&connection, &skills[0].name), skills[0].goal_reps);
+        assert_eq!(goal_of(&connection, &skills[1].name), 1234);
+        assert_eq!(get_active_profile(&connection).unwrap(), None);
+
+        set_goals_for_type(&connection, Some(&SkillTypes::Yoga), 6000).unwrap();
+        assert!(Skill::get_all_skills(&connection)
+            .iter()
+            .filter(|s| s.skill_type == SkillTypes::Yoga)
+            .all(|s| s.goal_reps == 6000));
+
+        save_goal_profile(&connection, "Mine").unwrap();
+        apply_goal_profile(&connection, DOUBLE_PROFILE).unwrap();
+        assert_eq!(goal_of(&connection, "Squat"), title_goal * 2);
+
+        apply_goal_profile(&connection, "Mine").unwrap();
+        assert_eq!(goal_of(&connection, &skills[1].name), 1234);
+        assert_eq!(
+            get_active_profile(&connection).unwrap(),
+            Some("Mine".to_owned())
+        );
+
+        delete_goal_profile(&connection, "Mine").unwrap();
+        assert_eq!(
+            get_goal_profiles(&connection).unwrap(),
+            vec![DOUBLE_PROFILE.to_owned(), TITLE_PROFILE.to_owned()]
+        );
+        assert!(apply_goal_profile(&connection, "Mine").is_err());
+
+        // The built-in profiles stay the way they are.
+        assert!(delete_goal_profile(&connection, TITLE_PROFILE).is_err());
+        assert!(save_goal_profile(&connection, "2x challenge").is_err());
+        apply_goal_profile(&connection, TITLE_PROFILE).unwrap();
+        assert_eq!(goal_of(&connection, "Squat"), title_goal);
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, error::Error};
+
+use rusqlite::Connection;
+
+use crate::{skills::Skill, workout::save_adjustment_to_db};
+
+/// A skill whose completed reps do not match the reps found in the history.
+#[derive(Debug)]
+pub struct RepDiscrepancy {
+    pub skill: Skill,
+    pub history_reps: i64,
+}
+
+impl RepDiscrepancy {
+    #[must_use]
+    /// Gets the reps that are missing from the history, negative if the history has too many.
+    pub fn get_difference(&self) -> i64 {
+        self.skill.completed_reps as i64 - self.history_reps
+    }
+}
+
+/// Sums up the reps of every skill across all workouts and manual adjustments.
+pub fn get_history_totals(connection: &Connection) -> Result<HashMap<String, i64>, Box<dyn Error>> {
+    let mut stmt = connection.prepare(
+        "
+            SELECT s.name,
+                COALESCE((SELECT SUM(w.reps) FROM workout_skills w WHERE w.skill = s.name), 0)
+                + COALESCE((SELECT SUM(a.new_reps - a.old_reps) FROM rep_adjustments a WHERE a.skill = s.name), 0)
+            FROM skills s
+        ",
+    )?;
+
+    let totals = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+
+    Ok(totals)
+}
+
+/// Compares the completed reps of every skill with the reps in the history
+/// and returns every skill where they differ.
+pub fn check_rep_totals(connection: &Connection) -> Result<Vec<RepDiscrepancy>, Box<dyn Error>> {
+    let totals = get_history_totals(connection)?;
+
+    Ok(Skill::get_all_skills(connection)
+        .into_iter()
+        .filter_map(|skill| {
+            let history_reps = totals.get(&skill.name).copied().unwrap_or(0);
+            if history_reps == skill.completed_reps as i64 {
+                None
+            } else {
+                Some(RepDiscrepancy {
+                    skill,
+                    history_reps,
+                })
+            }
+        })
+        .collect())
+}
+
+/// Records a manual adjustment for every discrepancy, going from the reps in the history to the completed reps.
+/// The completed reps stay the same, afterwards the history explains every total.
+pub fn reconcile_rep_totals(
+    connection: &Connection,
+    discrepancies: &[RepDiscrepancy],
+) -> Result<(), Box<dyn Error>> {
+    let current_time = chrono::offset::Local::now();
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for discrepancy in discrepancies {
+        save_adjustment_to_db(
+            &transaction,
+            current_time,
+            &discrepancy.skill,
+            discrepancy.history_reps,
+            discrepancy.skill.completed_reps as i64,
+        )?;
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::test::test_connection,
+        workout::{insert_workout_to_db, Workout},
+    };
+    use chrono::Local;
+
+    #[test]
+    fn test_check_and_reconcile() {
+        let connection = test_connection();
+
+        assert!(check_rep_totals(&connection).unwrap().is_empty());
+
+        let skills = Skill::get_all_skills(&connection);
+        let squat = skills.iter().find(|s| s.name == "Squat").unwrap();
+        let plank = skills.iter().find(|s| s.name == "Plank").unwrap();
+
+        // A workout that never made it into the totals, and a total without any workout.
+        let workout = Workout {
+            id: 0,
+            skill: vec![(squat.clone(), 50)],
+        };
+        insert_workout_to_db(&connection, Local::now(), &workout).unwrap();
+        plank.set_reps(&connection, 200).unwrap();
+
+        let discrepancies = check_rep_totals(&connection).unwrap();
+        assert_eq!(discrepancies.len(), 2);
+
+        let squat_discrepancy = discrepancies.iter().find(|d| &d.skill == squat).unwrap();
+        assert_eq!(squat_discrepancy.history_reps, 50);
+        assert_eq!(squat_discrepancy.get_difference(), -50);
+
+        let plank_discrepancy = discrepancies.iter().find(|d| &d.skill == plank).unwrap();
+        assert_eq!(plank_discrepancy.get_difference(), 200);
+
+        reconcile_rep_totals(&connection, &discrepancies).unwrap();
+
+        assert!(check_rep_totals(&connection).unwrap().is_empty());
+    }
+}
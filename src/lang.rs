@@ -1,64 +1,74 @@
 #![allow(clippy::use_self)]
 
-use std::{collections::HashMap, error::Error, str::FromStr};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    error::Error,
+    fs::read_dir,
+};
 
 use rusqlite::Connection;
-use serde::{Deserialize, Serialize};
-use serde_json::{Map, Value};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::{
     app::RingFitApp,
-    settings::Settings,
-    skills::{Skill, SkillHashtags},
+    format::{
+        Locale, DATE_FORMAT_KEY, DECIMAL_SEPARATOR_KEY, MONTH_FORMAT_KEY, PERCENT_FORMAT_KEY,
+        THOUSANDS_SEPARATOR_KEY, TIME_FORMAT_KEY,
+    },
+    paths::DataDir,
+    settings::{load_settings, save_settings},
+    skills::{all_skills_default, Skill, SkillHashtags},
 };
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
-/// The currently supported languages.
-pub enum Languages {
-    English,
-    German,
-}
+// The language everything is written in first, used if nothing else is chosen.
+pub const DEFAULT_LANGUAGE: &str = "en";
+// Every language has its own name in its own language under this key.
+pub const LANGUAGE_NAME_KEY: &str = "lang_names";
 
-/// The translation consists of a "Key" and X Values,
-/// X = Number of Languages supported.
-/// All of which are Strings, of course.
-type Translation = (String, String, String);
+#[derive(Serialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+/// A language, identified by the code used for it in the translation files, like "en" or "de".
+pub struct Language(pub String);
 
-impl ToString for Languages {
-    fn to_string(&self) -> String {
-        match self {
-            Self::English => "English".into(),
-            Self::German => "Deutsch".into(),
-        }
+/// A translation consists of a key, the code of the language and the translated text.
+pub type Translation = (String, String, String);
+
+impl Default for Language {
+    fn default() -> Self {
+        Self(DEFAULT_LANGUAGE.into())
     }
 }
 
-impl FromStr for Languages {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "English" => Ok(Self::English),
-            "Deutsch" | "German" => Ok(Self::German),
-            _ => Err(()),
-        }
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
-/// Switches the display language to the target language.
-pub fn switch_language(rfa: &mut RingFitApp, target_language: Languages) {
-    rfa.language = target_language;
+// Older settings files and backups store the language by its name instead of its code.
+impl<'de> Deserialize<'de> for Language {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
 
-    let settings = Settings {
-        language: target_language,
-    };
-    let s = serde_json::to_string_pretty(&settings).expect("Could not serialize json to string");
+        Ok(match code.as_str() {
+            "English" => Self("en".into()),
+            "German" | "Deutsch" => Self("de".into()),
+            _ => Self(code),
+        })
+    }
+}
 
-    std::fs::write("./settings/settings.json", s).expect("Could not write to settings.json");
+/// Switches the display language to the target language.
+pub fn switch_language(rfa: &mut RingFitApp, target_language: Language) {
+    let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+    settings.language = target_language.clone();
+    save_settings(&rfa.data_dir, &settings).expect("Could not write to settings.json");
 
     let (skill_hashmap, hashtag_hashmap, menu_hashmap) =
-        get_language_hashmaps(&rfa.db_connection, target_language);
+        get_language_hashmaps(&rfa.db_connection, &target_language);
 
+    rfa.language = target_language;
+    rfa.locale = Locale::from_strings(&menu_hashmap);
     rfa.skill_names = skill_hashmap;
     rfa.hashtag_names = hashtag_hashmap;
     rfa.menu_names = menu_hashmap;
@@ -68,7 +78,7 @@ pub fn switch_language(rfa: &mut RingFitApp, target_language: Languages) {
 /// Gets all of the translation hashmaps of a specified language.
 pub fn get_language_hashmaps(
     connection: &Connection,
-    target_language: Languages,
+    target_language: &Language,
 ) -> (
     HashMap<Skill, String>,
     HashMap<SkillHashtags, String>,
@@ -76,101 +86,390 @@ pub fn get_language_hashmaps(
 ) {
     let all_skills = Skill::get_all_skills(connection);
     let all_hashtags = SkillHashtags::get_all_hashtags();
-    let all_menus = get_all_translations().expect("Could not read translations from database.");
 
     let mut skill_hashmap = HashMap::new();
     for skill in all_skills {
-        let name = skill.get_translated_name(connection, &target_language);
+        let name = skill.get_translated_name(connection, target_language);
         skill_hashmap.insert(skill, name);
     }
 
     let mut hashtag_hashmap = HashMap::new();
     for hashtag in all_hashtags {
-        let name = hashtag.get_translated_name(connection, &target_language);
+        let name = hashtag.get_translated_name(connection, target_language);
         hashtag_hashmap.insert(hashtag, name);
     }
 
-    let mut menu_hashmap = HashMap::new();
-    for item in all_menus {
-        match target_language {
-            Languages::English => menu_hashmap.insert(item.0, item.1),
-            Languages::German => menu_hashmap.insert(item.0, item.2),
-        };
-    }
+    // Strings missing in the target language are shown in English instead.
+    let mut menu_hashmap = get_language_strings(connection, &Language::default())
+        .expect("Could not read translations from database.");
+    menu_hashmap.extend(
+        get_language_strings(connection, target_language)
+            .expect("Could not read translations from database."),
+    );
 
     (skill_hashmap, hashtag_hashmap, menu_hashmap)
 }
 
-/// Gets every translation in the translations.json file
-/// and converts it into a Vector of Translations, aka (String, String, String).
-pub fn get_all_translations() -> Result<Vec<Translation>, Box<dyn Error>> {
-    let file_content = include_str!("../assets/translations.json");
+/// Parses the content of a translation file, which has the text in every language for every key:
+/// `{ "back": { "en": "Back", "de": "Zurück" } }`
+pub fn parse_translations(file_content: &str) -> Result<Vec<Translation>, Box<dyn Error>> {
+    let file: HashMap<String, HashMap<String, String>> = serde_json::from_str(file_content)?;
+
+    let mut translations = Vec::new();
+
+    for (key, texts) in file {
+        for (language, text) in texts {
+            translations.push((key.clone(), language, text));
+        }
+    }
+
+    Ok(translations)
+}
 
-    let v: Value = serde_json::from_str(file_content)?;
+/// Gets every translation in the translations.json file bundled with the program.
+pub fn get_bundled_translations() -> Result<Vec<Translation>, Box<dyn Error>> {
+    parse_translations(include_str!("../assets/translations.json"))
+}
 
+/// Gets the translations from every .json file in the languages folder of the data directory.
+/// These can add new languages or change the bundled translations.
+pub fn get_user_translations(data_dir: &DataDir) -> Result<Vec<Translation>, Box<dyn Error>> {
     let mut translations = Vec::new();
 
-    for (key, value) in v.as_object().unwrap_or(&Map::new()) {
-        translations.push((
-            key.clone(),
-            // .to_string() would leave the "" unchanged,
-            // .as_str() removes them but we need to unwrap and convert after.
-            value.as_array().unwrap_or(&Vec::new())[0]
-                .as_str()
-                .unwrap_or("")
-                .into(),
-            value.as_array().unwrap_or(&Vec::new())[1]
-                .as_str()
-                .unwrap_or("")
-                .into(),
-        ));
+    let entries = match read_dir(data_dir.languages()) {
+        Ok(entries) => entries,
+        // Without the folder there just are no user translations.
+        Err(_) => return Ok(translations),
+    };
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let file_content = std::fs::read_to_string(&path)?;
+        translations.extend(
+            parse_translations(&file_content)
+                .map_err(|e| format!("Could not read {}: {}", path.display(), e))?,
+        );
     }
 
     Ok(translations)
 }
 
+/// Gets the bundled translations followed by the ones of the user,
+/// so that the latter replace the former when they are saved in order.
+pub fn get_all_translations(data_dir: &DataDir) -> Result<Vec<Translation>, Box<dyn Error>> {
+    let mut translations = get_bundled_translations()?;
+    translations.extend(get_user_translations(data_dir)?);
+
+    Ok(translations)
+}
+
+/// Gets every language in the database with its name in that language, sorted by that name.
+pub fn get_languages(connection: &Connection) -> Result<Vec<(Language, String)>, Box<dyn Error>> {
+    let mut stmt = connection.prepare(
+        "
+            SELECT languages.lang, COALESCE(names.text, languages.lang) AS name
+            FROM (SELECT DISTINCT lang FROM translations) AS languages
+            LEFT JOIN translations AS names ON names.lang = languages.lang AND names.key = ?
+            ORDER BY name
+        ",
+    )?;
+
+    let languages = stmt
+        .query_map([LANGUAGE_NAME_KEY], |row| {
+            Ok((Language(row.get(0)?), row.get(1)?))
+        })?
+        .collect::<Result<Vec<(Language, String)>, _>>()?;
+
+    Ok(languages)
+}
+
+/// Gets every translated string of a language by its key.
+pub fn get_language_strings(
+    connection: &Connection,
+    language: &Language,
+) -> Result<HashMap<String, String>, Box<dyn Error>> {
+    let mut stmt = connection.prepare("SELECT key, text FROM translations WHERE lang = ?")?;
+
+    let strings = stmt
+        .query_map([&language.0], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<HashMap<String, String>, _>>()?;
+
+    Ok(strings)
+}
+
 /// Gets a translated string directly from the database given the target language and the key value.
+/// If the language does not have the string, the English one is used, and if there is none either, the key itself.
 pub fn get_string(
     connection: &Connection,
-    language: &Languages,
+    language: &Language,
     key: String,
 ) -> Result<String, Box<dyn Error>> {
-    let index = match language {
-        Languages::English => "en",
-        Languages::German => "de",
-    };
+    let mut stmt = connection.prepare(
+        "SELECT text FROM translations WHERE key = ?1 AND lang IN (?2, ?3) ORDER BY lang = ?2 DESC",
+    )?;
+
+    let translation = stmt.query_row([&key, &language.0, DEFAULT_LANGUAGE], |r| r.get(0));
 
-    let mut stmt = connection.prepare(&format!(
-        "SELECT {} FROM translations WHERE key = :key",
-        index
-    ))?;
+    match translation {
+        Ok(translation) => Ok(translation),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(key),
+        Err(e) => Err(e.into()),
+    }
+}
 
-    let translation: String = stmt.query_row([key], |r| r.get(0))?;
+/// The problems found in a set of translations.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TranslationReport {
+    // The keys every language is missing, sorted by language.
+    pub missing: Vec<(Language, Vec<String>)>,
+    // Keys the program does not know, like misspelled ones, which are never shown.
+    pub unknown: Vec<String>,
+    // Bundled keys that are not shown anywhere anymore.
+    pub unused: Vec<String>,
+}
 
-    Ok(translation)
+impl TranslationReport {
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.unknown.is_empty() && self.unused.is_empty()
+    }
+}
+
+/// Gets the keys the program looks up by themselves instead of writing them out in the menus.
+fn get_name_keys() -> Vec<String> {
+    let mut keys: Vec<String> = [
+        LANGUAGE_NAME_KEY,
+        DATE_FORMAT_KEY,
+        MONTH_FORMAT_KEY,
+        TIME_FORMAT_KEY,
+        THOUSANDS_SEPARATOR_KEY,
+        DECIMAL_SEPARATOR_KEY,
+        PERCENT_FORMAT_KEY,
+    ]
+    .iter()
+    .map(|k| (*k).to_owned())
+    .collect();
+
+    keys.extend(all_skills_default().iter().map(Skill::get_key));
+    keys.extend(
+        SkillHashtags::get_all_hashtags()
+            .iter()
+            .map(|h| h.get_key().to_owned()),
+    );
+
+    keys
+}
+
+#[must_use]
+/// Gets the keys of `translations` that are shown nowhere, neither in the menus, the windows outside of them,
+/// nor as the name of a skill, hashtag or language.
+pub fn get_unused_keys(translations: &[Translation]) -> Vec<String> {
+    // The menus write out every key they look up.
+    let source = [include_str!("menu.rs"), include_str!("app.rs")].concat();
+    let name_keys = get_name_keys();
+
+    translations
+        .iter()
+        .map(|(key, _, _)| key)
+        .filter(|key| !name_keys.contains(key) && !source.contains(&format!("\"{}\"", key)))
+        .collect::<BTreeSet<&String>>()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+#[must_use]
+/// Checks every bundled language and every language in `translations` against the keys of the bundled English
+/// translations. A language counts as complete if every key is either in `translations` or in `bundled`.
+pub fn check_translations(
+    bundled: &[Translation],
+    translations: &[Translation],
+) -> TranslationReport {
+    let known_keys: BTreeSet<&String> = bundled
+        .iter()
+        .filter(|(_, lang, _)| lang == DEFAULT_LANGUAGE)
+        .map(|(key, _, _)| key)
+        .collect();
+    let languages: BTreeSet<&String> = bundled
+        .iter()
+        .chain(translations)
+        .map(|(_, lang, _)| lang)
+        .collect();
+
+    let mut missing = Vec::new();
+    for language in languages {
+        let translated: HashSet<&String> = bundled
+            .iter()
+            .chain(translations)
+            .filter(|(_, lang, _)| lang == language)
+            .map(|(key, _, _)| key)
+            .collect();
+
+        let missing_keys: Vec<String> = known_keys
+            .iter()
+            .filter(|key| !translated.contains(*key))
+            .map(|key| (*key).clone())
+            .collect();
+
+        if !missing_keys.is_empty() {
+            missing.push((Language(language.clone()), missing_keys));
+        }
+    }
+
+    let unknown = translations
+        .iter()
+        .map(|(key, _, _)| key)
+        .filter(|key| !known_keys.contains(key))
+        .collect::<BTreeSet<&String>>()
+        .into_iter()
+        .cloned()
+        .collect();
+
+    TranslationReport {
+        missing,
+        unknown,
+        unused: get_unused_keys(bundled),
+    }
+}
+
+/// Checks the bundled translations and the files in the languages folder of the data directory
+/// against the bundled English ones.
+pub fn check_all_translations(data_dir: &DataDir) -> Result<TranslationReport, Box<dyn Error>> {
+    Ok(check_translations(
+        &get_bundled_translations()?,
+        &get_user_translations(data_dir)?,
+    ))
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::db::{test::test_connection, update_translations};
+
+    #[test]
+    fn test_old_language_names() {
+        let german: Language = serde_json::from_str("\"Deutsch\"").unwrap();
+        let english: Language = serde_json::from_str("\"English\"").unwrap();
+        let other: Language = serde_json::from_str("\"fr\"").unwrap();
+
+        assert_eq!(german, Language("de".into()));
+        assert_eq!(english, Language("en".into()));
+        assert_eq!(other, Language("fr".into()));
+        assert_eq!(serde_json::to_string(&german).unwrap(), "\"de\"");
+    }
+
+    #[test]
+    fn test_user_languages() {
+        let connection = test_connection();
+
+        let bundled = get_bundled_translations().unwrap();
+        let mut translations = bundled.clone();
+        translations.extend(
+            parse_translations(
+                r#"{ "lang_names": { "fr": "Français" }, "back": { "fr": "Retour", "de": "Zurück!" } }"#,
+            )
+            .unwrap(),
+        );
+        update_translations(&connection, &translations).unwrap();
+
+        assert_eq!(
+            get_languages(&connection).unwrap(),
+            vec![
+                (Language("de".into()), "Deutsch".into()),
+                (Language("en".into()), "English".into()),
+                (Language("fr".into()), "Français".into()),
+            ]
+        );
+
+        let french = Language("fr".into());
+        assert_eq!(
+            get_string(&connection, &french, "back".into()).unwrap(),
+            "Retour"
+        );
+        // User translations replace the bundled ones.
+        assert_eq!(
+            get_string(&connection, &Language("de".into()), "back".into()).unwrap(),
+            "Zurück!"
+        );
+        assert_eq!(get_language_strings(&connection, &french).unwrap().len(), 2);
+
+        // Missing strings fall back to English, and then to the key.
+        assert_eq!(
+            get_string(&connection, &french, "skill_squat".into()).unwrap(),
+            "Squat"
+        );
+        assert_eq!(
+            get_string(&connection, &french, "not_a_key".into()).unwrap(),
+            "not_a_key"
+        );
+        let (_, _, menu_hashmap) = get_language_hashmaps(&connection, &french);
+        assert_eq!(menu_hashmap.get("back").unwrap(), "Retour");
+        assert_eq!(menu_hashmap.get("skill").unwrap(), "Skill");
+
+        // Once the file is deleted, the language and its changes are gone.
+        update_translations(&connection, &bundled).unwrap();
+        assert_eq!(get_languages(&connection).unwrap().len(), 2);
+        assert!(get_language_strings(&connection, &french)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            get_string(&connection, &Language("de".into()), "back".into()).unwrap(),
+            "Zurück"
+        );
+    }
 
     #[test]
-    fn test_lang_string_conv() {
-        let german_string = "Deutsch";
-        let english_string = "English";
-        let invalid_string = "Something else";
+    fn test_check_translations() {
+        let bundled = parse_translations(
+            r#"{ "back": { "en": "Back", "de": "Zurück" }, "confirm": { "en": "Confirm" } }"#,
+        )
+        .unwrap();
+        let translations = parse_translations(
+            r#"{ "back": { "fr": "Retour" }, "confirm": { "de": "Bestätigen" }, "bakc": { "fr": "Retour" } }"#,
+        )
+        .unwrap();
 
-        assert_eq!(german_string, &Languages::German.to_string());
-        assert_eq!(english_string, &Languages::English.to_string());
+        let report = check_translations(&bundled, &translations);
 
         assert_eq!(
-            Languages::from_str(german_string).unwrap(),
-            Languages::German
+            report,
+            TranslationReport {
+                missing: vec![(Language("fr".into()), vec!["confirm".into()])],
+                unknown: vec!["bakc".into()],
+                unused: Vec::new(),
+            }
         );
+        assert!(!report.is_complete());
+
+        // Bundled languages are checked without any files of the user.
+        let bundled = parse_translations(
+            r#"{ "back": { "en": "Back", "de": "Zurück" }, "confirm": { "en": "Confirm" }, "old_key": { "en": "Old" } }"#,
+        )
+        .unwrap();
         assert_eq!(
-            Languages::from_str(english_string).unwrap(),
-            Languages::English
+            check_translations(&bundled, &[]),
+            TranslationReport {
+                missing: vec![(
+                    Language("de".into()),
+                    vec!["confirm".into(), "old_key".into()]
+                )],
+                unknown: Vec::new(),
+                unused: vec!["old_key".into()],
+            }
         );
-        assert_eq!(Languages::from_str(invalid_string), Err(()));
+    }
+
+    #[test]
+    fn test_bundled_translations_complete() {
+        let bundled = get_bundled_translations().unwrap();
+
+        // Every bundled language has every key, and every key is shown somewhere.
+        let report = check_translations(&bundled, &[]);
+        assert!(report.is_complete(), "{:#?}", report);
     }
 }
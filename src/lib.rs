@@ -1,7 +1,23 @@
 pub mod app;
+pub mod backup;
+pub mod balance;
+pub mod catalog;
+pub mod chart;
+pub mod cli;
+pub mod csv;
+pub mod custom;
 pub mod db;
+pub mod forecast;
+pub mod format;
+pub mod goals;
+pub mod integrity;
 pub mod lang;
 pub mod menu;
+pub mod paths;
+pub mod planner;
+pub mod schedule;
 pub mod settings;
 pub mod skills;
+pub mod stats;
+pub mod unlocks;
 pub mod workout;
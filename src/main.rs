@@ -1,35 +1,35 @@
 use std::{
     error::Error,
-    fs::{create_dir_all, write, File},
+    fs::{create_dir_all, File},
 };
 
 use eframe::IconData;
 use egui::Vec2;
-use rfa_tracker::{app::RingFitApp, db::setup_db, lang::Languages, settings::Settings};
+use rfa_tracker::{
+    app::RingFitApp,
+    cli::{parse_args, run_command, USAGE},
+    db::setup_db,
+    paths::DataDir,
+    settings::{save_settings, Settings},
+};
+use rusqlite::Connection;
 
 /// Sets up the required files and folders for first time usage.
-fn first_time_setup() -> Result<(), Box<dyn Error>> {
+fn first_time_setup(data_dir: &DataDir) -> Result<(), Box<dyn Error>> {
+    create_dir_all(&data_dir.path)?;
+
     // Trying to see if the file exists and if not, create it and run first time setup.
-    match File::open("./settings/settings.json") {
+    match File::open(data_dir.settings()) {
         Ok(_) => (),
         Err(_) => {
-            create_dir_all("./settings/")?;
-            File::create("./settings/settings.json")?;
-            let settings = Settings {
-                language: Languages::English,
-            };
-            let s = serde_json::to_string_pretty(&settings)?;
-
-            write("./settings/settings.json", s)?;
+            save_settings(data_dir, &Settings::default())?;
         }
     };
 
-    match File::open("./db/database.db") {
+    match File::open(data_dir.database()) {
         Ok(_) => (),
         Err(_) => {
-            create_dir_all("./db/")?;
-            File::create("./db/database.db")?;
-            setup_db()?;
+            File::create(data_dir.database())?;
         }
     };
 
@@ -37,7 +37,26 @@ fn first_time_setup() -> Result<(), Box<dyn Error>> {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    first_time_setup()?;
+    let args: Vec<String> = std::env::args().collect();
+    let data_dir = DataDir::resolve(&args);
+
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    first_time_setup(&data_dir)?;
+    // The database gets migrated on every startup, not just the first one.
+    setup_db(&data_dir)?;
+
+    // If a command is given, we run it and exit without opening the window.
+    if let Some(command) = command {
+        let connection = Connection::open(data_dir.database())?;
+        return run_command(&connection, &data_dir, &command, &mut std::io::stdout());
+    }
 
     let image_bytes = include_bytes!("../assets/icon_arms.png");
     let image_data = image::load_from_memory(image_bytes)?.to_rgba8();
@@ -57,7 +76,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     eframe::run_native(
         "Ring Fit Adventure Tracker",
         options,
-        Box::new(|_cc| Box::new(RingFitApp::default())),
+        Box::new(|_cc| Box::new(RingFitApp::new(data_dir))),
     );
 
     Ok(())
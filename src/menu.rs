@@ -1,23 +1,67 @@
-use chrono::{Datelike, Timelike};
+use std::error::Error;
+
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
 use egui::{
-    CentralPanel, Color32, ComboBox, Context, FontId, Grid, Image, Label, ProgressBar, RichText,
-    ScrollArea, Window,
+    plot::{Line, Plot, PlotPoints},
+    Button, CentralPanel, Color32, ComboBox, Context, DragValue, FontId, Grid, Image, Label,
+    ProgressBar, RichText, ScrollArea, Window,
 };
+use egui_extras::DatePickerButton;
 
 use crate::{
     app::RingFitApp,
-    lang::{switch_language, Languages},
-    skills::{Skill, SkillHashtags, SkillHits, SkillTypes},
-    workout::{get_workouts_from_db, save_workout_to_db},
+    backup::{
+        create_backup, diff_backup, get_auto_backups, read_backup, restore_backup, write_backup,
+    },
+    balance::{get_balance, Period},
+    chart::{get_progress_over_time, Bucket, ChartSelection},
+    csv::{
+        export_progress, export_workouts, import_workouts, parse_workouts_csv, preview_import,
+        ImportMode,
+    },
+    custom::{add_custom_skill, delete_custom_skill},
+    forecast::{forecast_all, forecast_skill, get_recent_reps, Forecast},
+    format::Locale,
+    goals::{
+        apply_goal_profile, delete_goal_profile, get_active_profile, get_goal_profiles,
+        is_built_in_profile, save_goal_profile, save_goals_to_db, set_goals_for_type,
+    },
+    integrity::{check_rep_totals, reconcile_rep_totals},
+    lang::{get_languages, switch_language},
+    planner::{plan_session, SessionTarget},
+    schedule::Schedule,
+    settings::{load_settings, save_settings},
+    skills::{Skill, SkillHashtags, SkillHits, SkillTypes, MAX_LEVEL},
+    stats::{get_daily_reps, get_stats, get_training_day},
+    unlocks::{get_next_unlocks, get_upcoming_unlocks, MAX_PLAYER_LEVEL},
+    workout::{
+        delete_workout_from_db, get_history_from_db, get_monthly_reps, get_workout_from_db,
+        get_workouts_from_db, log_workout_to_db, save_manual_reps_to_db, update_workout_in_db,
+        HistoryEntry,
+    },
 };
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Menu {
     LogWorkout(bool),
     ViewProgress,
-    ViewWorkouts,
+    Charts,
+    Stats,
+    // The day we show the workouts of, if any.
+    Calendar(Option<NaiveDate>),
+    Balance,
+    Schedule,
+    // The workout we are about to delete, if any.
+    ViewWorkouts(Option<i64>),
+    EditWorkout(i64, bool),
     ViewSkills,
+    Unlocks,
     SetReps(bool),
+    Goals(bool),
+    CustomSkills,
+    CheckTotals(bool),
+    ImportExport(bool),
+    Backup(bool),
     LanguageChoice,
 }
 
@@ -40,6 +84,27 @@ pub fn display_menu(rfa: &mut RingFitApp, ctx: &Context) {
         Some(Menu::LogWorkout(_)) => {
             log_workout(rfa, ctx);
         }
+        Some(Menu::Charts) => {
+            charts(rfa, ctx);
+        }
+        Some(Menu::Stats) => {
+            stats(rfa, ctx);
+        }
+        Some(Menu::Calendar(_)) => {
+            calendar(rfa, ctx);
+        }
+        Some(Menu::Balance) => {
+            balance(rfa, ctx);
+        }
+        Some(Menu::Schedule) => {
+            schedule(rfa, ctx);
+        }
+        Some(Menu::Goals(_)) => {
+            goals(rfa, ctx);
+        }
+        Some(Menu::CustomSkills) => {
+            custom_skills(rfa, ctx);
+        }
         Some(Menu::ViewProgress) => {
             view_progess(rfa, ctx);
         }
@@ -49,16 +114,114 @@ pub fn display_menu(rfa: &mut RingFitApp, ctx: &Context) {
         Some(Menu::ViewSkills) => {
             view_skills(rfa, ctx);
         }
+        Some(Menu::Unlocks) => {
+            unlocks(rfa, ctx);
+        }
+        Some(Menu::CheckTotals(_)) => {
+            check_totals(rfa, ctx);
+        }
+        Some(Menu::ImportExport(_)) => {
+            import_export(rfa, ctx);
+        }
+        Some(Menu::Backup(_)) => {
+            backup(rfa, ctx);
+        }
         Some(Menu::LanguageChoice) => {
             language_choice(rfa, ctx);
         }
-        Some(Menu::ViewWorkouts) => {
+        Some(Menu::ViewWorkouts(_)) => {
             view_workouts(rfa, ctx);
         }
+        Some(Menu::EditWorkout(_, _)) => {
+            edit_workout(rfa, ctx);
+        }
         None => {
             main_menu(rfa, ctx);
         }
     }
+
+    if rfa.legacy_data {
+        legacy_data_window(rfa, ctx);
+    }
+
+    if rfa.error_message.is_some() {
+        error_window(rfa, ctx);
+    }
+}
+
+/// Offers to move the data of older versions, which was stored in the working directory, into the data directory.
+pub fn legacy_data_window(rfa: &mut RingFitApp, ctx: &Context) {
+    Window::new(
+        rfa.menu_names
+            .get("move_data")
+            .unwrap_or(&"Move Data".to_owned()),
+    )
+    .collapsible(false)
+    .resizable(false)
+    .show(ctx, |ui| {
+        ui.label(format!(
+            "{}: {}",
+            rfa.menu_names
+                .get("show_move_data")
+                .unwrap_or(&"Found data from an older version in ./db. Move it into the data directory? This replaces the data there".to_owned()),
+            rfa.data_dir.path.display()
+        ));
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("confirm")
+                            .unwrap_or(&"Confirm".to_owned()),
+                    )
+                    .color(CONFIRM_COLOR),
+                )
+                .clicked()
+            {
+                // The data directory might already have workouts logged since the upgrade,
+                // so we keep a backup of it before it gets replaced.
+                if !rfa.auto_backup() {
+                    rfa.legacy_data = false;
+                } else if let Err(e) = rfa.move_legacy_data() {
+                    rfa.legacy_data = false;
+                    rfa.error_message = Some(e.to_string());
+                }
+            }
+
+            // We only ask again on the next start.
+            if ui
+                .button(
+                    RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
+                        .color(CANCEL_COLOR),
+                )
+                .clicked()
+            {
+                rfa.legacy_data = false;
+            }
+        });
+    });
+}
+
+/// Shows the last error that happened on top of the current menu, until it gets dismissed.
+pub fn error_window(rfa: &mut RingFitApp, ctx: &Context) {
+    Window::new(rfa.menu_names.get("error").unwrap_or(&"Error".to_owned()))
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new(rfa.error_message.as_deref().unwrap_or_default()).color(CANCEL_COLOR),
+            );
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(rfa.menu_names.get("ok").unwrap_or(&"OK".to_owned()))
+                .clicked()
+            {
+                rfa.error_message = None;
+            }
+        });
 }
 
 /// The main menu, with all of the buttons for the sub menus.
@@ -100,6 +263,52 @@ pub fn main_menu(rfa: &mut RingFitApp, ctx: &Context) {
         {
             rfa.menu = Some(Menu::ViewProgress);
         }
+        if ui
+            .button(rfa.menu_names.get("charts").unwrap_or(&"Charts".to_owned()))
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Charts);
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("stats")
+                    .unwrap_or(&"Statistics".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Stats);
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("calendar")
+                    .unwrap_or(&"Calendar".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Calendar(None));
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("balance")
+                    .unwrap_or(&"Balance".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Balance);
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("schedule")
+                    .unwrap_or(&"Schedule".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Schedule);
+        }
         if ui
             .button(
                 rfa.menu_names
@@ -108,7 +317,7 @@ pub fn main_menu(rfa: &mut RingFitApp, ctx: &Context) {
             )
             .clicked()
         {
-            rfa.menu = Some(Menu::ViewWorkouts);
+            rfa.menu = Some(Menu::ViewWorkouts(None));
         }
         if ui
             .button(
@@ -120,6 +329,16 @@ pub fn main_menu(rfa: &mut RingFitApp, ctx: &Context) {
         {
             rfa.menu = Some(Menu::ViewSkills);
         }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("unlocks_menu")
+                    .unwrap_or(&"Skill unlocks".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Unlocks);
+        }
         if ui
             .button(
                 rfa.menu_names
@@ -130,6 +349,52 @@ pub fn main_menu(rfa: &mut RingFitApp, ctx: &Context) {
         {
             rfa.menu = Some(Menu::SetReps(false));
         }
+        if ui
+            .button(rfa.menu_names.get("goals").unwrap_or(&"Goals".to_owned()))
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Goals(false));
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("custom_skills")
+                    .unwrap_or(&"Custom skills".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::CustomSkills);
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("check_totals")
+                    .unwrap_or(&"Check rep totals".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::CheckTotals(false));
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("import_export")
+                    .unwrap_or(&"Import & Export".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::ImportExport(false));
+        }
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("backup")
+                    .unwrap_or(&"Backup & Restore".to_owned()),
+            )
+            .clicked()
+        {
+            rfa.menu = Some(Menu::Backup(false));
+        }
         // This is always english, just in case you misclick to some language you do not speak and want to switch back.
         if ui.button("Change Language").clicked() {
             rfa.menu = Some(Menu::LanguageChoice);
@@ -150,6 +415,60 @@ pub fn log_workout(rfa: &mut RingFitApp, ctx: &Context) {
         }
         ui.add_space(HEADER_SIZE);
 
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("session_size")
+                    .unwrap_or(&"Session size".to_owned()),
+            );
+            ui.add(DragValue::new(&mut rfa.plan_amount).clamp_range(1..=10000));
+            ui.radio_value(
+                &mut rfa.plan_in_minutes,
+                false,
+                rfa.menu_names.get("reps").unwrap_or(&"Reps".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.plan_in_minutes,
+                true,
+                rfa.menu_names.get("minutes").unwrap_or(&"Minutes".to_owned()),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("max_per_type")
+                    .unwrap_or(&"Skills per type".to_owned()),
+            );
+            ui.add(DragValue::new(&mut rfa.plan_max_per_type).clamp_range(1..=10));
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("suggest_workout")
+                        .unwrap_or(&"Suggest a workout".to_owned()),
+                )
+                .on_hover_text(
+                    rfa.menu_names
+                        .get("show_suggest_workout")
+                        .unwrap_or(&"Fills in the skills furthest from their goal, preferring neglected muscle groups".to_owned()),
+                )
+                .clicked()
+            {
+                let target = if rfa.plan_in_minutes {
+                    SessionTarget::Minutes(rfa.plan_amount)
+                } else {
+                    SessionTarget::Reps(rfa.plan_amount)
+                };
+                let balance = get_balance(
+                    &get_workouts_from_db(&rfa.db_connection),
+                    Period::Month.get_start(Local::now()),
+                );
+
+                rfa.input_reps = plan_session(&rfa.skills, &balance, target, rfa.plan_max_per_type);
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
         ScrollArea::new([true, true]).show(ui, |ui| {
             Grid::new("RFA").striped(true).show(ui, |ui| {
                 let default_value = "Invalid".to_owned();
@@ -178,7 +497,10 @@ pub fn log_workout(rfa: &mut RingFitApp, ctx: &Context) {
                         RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".into()))
                             .color(color),
                     );
-                    ui.label(RichText::new(skill.completed_reps.to_string()).color(color));
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.completed_reps as i64))
+                            .color(color),
+                    );
                     ui.text_edit_singleline(&mut rfa.input_reps[i])
                         .on_hover_text(format!(
                             "{}: {}",
@@ -261,33 +583,26 @@ pub fn log_workout(rfa: &mut RingFitApp, ctx: &Context) {
                     )
                     .clicked()
                 {
-                    // First we set the reps for each skill.
-                    for (i, skill) in rfa.skills.iter().enumerate() {
-                        skill
-                            .update_reps(
-                                &rfa.db_connection,
-                                if rfa.input_reps[i].is_empty() {
-                                    0
-                                } else {
-                                    rfa.input_reps[i].parse().unwrap_or(0)
-                                },
-                            )
-                            .expect("Could not set reps in database.");
+                    // The reps and the workout are saved together, if anything fails nothing is saved at all.
+                    match log_workout_to_db(&rfa.db_connection, &rfa.skills, &rfa.input_reps) {
+                        Ok(_) => {
+                            // Then we pass the new values into the RingFitApp.
+                            let all_skills = Skill::get_all_skills(&rfa.db_connection);
+
+                            rfa.input_reps = vec!["".into(); all_skills.len()];
+                            rfa.skills = all_skills;
+                        }
+                        // The inputs are kept, so nothing is lost and you can just try again.
+                        Err(e) => {
+                            rfa.error_message = Some(format!(
+                                "{}: {}",
+                                rfa.menu_names
+                                    .get("workout_error")
+                                    .unwrap_or(&"Could not save the workout".to_owned()),
+                                e
+                            ));
+                        }
                     }
-
-                    // Then we save the workout into the database.
-                    save_workout_to_db(
-                        &rfa.db_connection,
-                        rfa.skills.clone(),
-                        rfa.input_reps.clone(),
-                    )
-                    .expect("Could not save workout to database.");
-
-                    // Then we pass the new values into the RingFitApp.
-                    let all_skills = Skill::get_all_skills(&rfa.db_connection);
-
-                    rfa.input_reps = vec!["".into(); all_skills.len()];
-                    rfa.skills = all_skills;
                     rfa.menu = Some(Menu::LogWorkout(false));
                 }
 
@@ -308,6 +623,14 @@ pub fn log_workout(rfa: &mut RingFitApp, ctx: &Context) {
 
 #[allow(clippy::redundant_closure_for_method_calls)]
 pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
+    let today = Local::now().naive_local().date();
+    let schedule_day = get_training_day(Local::now(), rfa.day_start_hour);
+    let recent_reps = get_recent_reps(
+        &rfa.db_connection,
+        Local::now() - Duration::days(rfa.forecast_days),
+    )
+    .unwrap_or_default();
+
     CentralPanel::default().show(ctx, |ui| {
         if ui
             .button(
@@ -320,6 +643,39 @@ pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
         }
         ui.add_space(HEADER_SIZE);
 
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("forecast_days")
+                    .unwrap_or(&"Forecast from the last days".to_owned()),
+            );
+            if ui
+                .add(DragValue::new(&mut rfa.forecast_days).clamp_range(1..=365))
+                .changed()
+            {
+                let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+                settings.forecast_days = rfa.forecast_days;
+                if let Err(e) = save_settings(&rfa.data_dir, &settings) {
+                    rfa.error_message = Some(e.to_string());
+                }
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        let format_forecast = |forecast: Forecast| match forecast {
+            Forecast::Done => rfa
+                .menu_names
+                .get("goal_reached")
+                .unwrap_or(&"Done".to_owned())
+                .clone(),
+            Forecast::Stalled => rfa
+                .menu_names
+                .get("stalled")
+                .unwrap_or(&"Stalled".to_owned())
+                .clone(),
+            Forecast::Date(date) => rfa.locale.format_date(date),
+        };
+
         ScrollArea::new([true, true]).show(ui, |ui| {
             Grid::new("progress").show(ui, |ui| {
                 let default_value = "Invalid".to_owned();
@@ -331,11 +687,18 @@ pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
                     rfa.menu_names
                         .get("progress_percent")
                         .unwrap_or(&default_value),
+                    rfa.menu_names.get("done_by").unwrap_or(&default_value),
                 ];
 
                 for text in headers {
                     ui.label(RichText::new(text).size(HEADER_SIZE));
                 }
+                if rfa.schedule.is_some() {
+                    ui.label(
+                        RichText::new(rfa.menu_names.get("ahead_behind").unwrap_or(&default_value))
+                            .size(HEADER_SIZE),
+                    );
+                }
                 ui.end_row();
 
                 for skill in &rfa.skills {
@@ -349,12 +712,39 @@ pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
                         x if x >= 25.0 => Color32::from_rgb(158, 21, 21),
                         _ => Color32::from_rgb(87, 16, 16),
                     };
-                    ui.label(RichText::new(skill.completed_reps.to_string()).color(color));
-                    ui.label(RichText::new(skill.get_reps_until_goal().to_string()).color(color));
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.completed_reps as i64))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.get_reps_until_goal() as i64))
+                            .color(color),
+                    );
                     ui.add(
                         ProgressBar::new(skill.get_rep_percent() as f32 / 100.0).show_percentage(),
                     )
-                    .on_hover_text(format!("{:.5}%", skill.get_rep_percent().to_string()));
+                    .on_hover_text(rfa.locale.format_percent(skill.get_rep_percent(), 5));
+                    ui.label(
+                        RichText::new(format_forecast(forecast_skill(
+                            skill,
+                            &recent_reps,
+                            rfa.forecast_days,
+                            today,
+                        )))
+                        .color(color),
+                    );
+                    if let Some(schedule) = &rfa.schedule {
+                        let difference = schedule.get_difference(skill, schedule_day);
+                        ui.label(
+                            RichText::new(rfa.locale.format_difference(difference)).color(
+                                if difference >= 0 {
+                                    CONFIRM_COLOR
+                                } else {
+                                    CANCEL_COLOR
+                                },
+                            ),
+                        );
+                    }
                     ui.end_row();
                 }
 
@@ -370,8 +760,8 @@ pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
                     .map(|s| s.get_reps_until_goal())
                     .sum::<usize>();
 
-                ui.label(RichText::new(all_sum.to_string()).strong());
-                ui.label(RichText::new(all_goal.to_string()).strong());
+                ui.label(RichText::new(rfa.locale.format_number(all_sum as i64)).strong());
+                ui.label(RichText::new(rfa.locale.format_number(all_goal as i64)).strong());
                 ui.vertical(|ui| {
                     let total_percent = 1.0
                         - (all_goal as f32
@@ -382,16 +772,52 @@ pub fn view_progess(rfa: &mut RingFitApp, ctx: &Context) {
                             / 100.0) as f32;
 
                     ui.add(ProgressBar::new(total_percent).show_percentage())
-                        .on_hover_text(format!("{:.5}%", total_percent * 100.0));
+                        .on_hover_text(rfa.locale.format_percent(total_percent as f64 * 100.0, 5));
                     ui.add(ProgressBar::new(relative_percent).show_percentage())
-                        .on_hover_text(format!("{:.5}%", relative_percent * 100.0));
+                        .on_hover_text(
+                            rfa.locale
+                                .format_percent(relative_percent as f64 * 100.0, 5),
+                        );
                 });
+                ui.label(
+                    RichText::new(format_forecast(forecast_all(
+                        &rfa.skills,
+                        &recent_reps,
+                        rfa.forecast_days,
+                        today,
+                    )))
+                    .strong(),
+                );
+                if let Some(schedule) = &rfa.schedule {
+                    let difference = rfa
+                        .skills
+                        .iter()
+                        .map(|s| schedule.get_difference(s, schedule_day))
+                        .sum::<i64>();
+                    ui.label(
+                        RichText::new(rfa.locale.format_difference(difference))
+                            .color(if difference >= 0 {
+                                CONFIRM_COLOR
+                            } else {
+                                CANCEL_COLOR
+                            })
+                            .strong(),
+                    );
+                }
             });
         });
     });
 }
 
-pub fn set_reps(rfa: &mut RingFitApp, ctx: &Context) {
+pub fn charts(rfa: &mut RingFitApp, ctx: &Context) {
+    let history = get_history_from_db(&rfa.db_connection);
+    let points = get_progress_over_time(
+        &history,
+        &rfa.skills,
+        &rfa.chart_selection,
+        rfa.chart_bucket,
+    );
+
     CentralPanel::default().show(ctx, |ui| {
         if ui
             .button(
@@ -404,150 +830,2023 @@ pub fn set_reps(rfa: &mut RingFitApp, ctx: &Context) {
         }
         ui.add_space(HEADER_SIZE);
 
-        ScrollArea::new([true, true]).show(ui, |ui| {
-            Grid::new("set_reps").striped(true).show(ui, |ui| {
-                let default_value = "Invalid".to_owned();
-
-                let headers = vec![
-                    rfa.menu_names.get("skill").unwrap_or(&default_value),
-                    rfa.menu_names.get("reps").unwrap_or(&default_value),
-                    rfa.menu_names.get("new_reps").unwrap_or(&default_value),
-                ];
-
-                for text in headers {
-                    ui.label(RichText::new(text).size(HEADER_SIZE));
-                }
-                ui.end_row();
+        let default_value = "Invalid".to_owned();
+        let skill_types = [
+            (SkillTypes::Arms, "arms"),
+            (SkillTypes::Core, "core"),
+            (SkillTypes::Legs, "legs"),
+            (SkillTypes::Yoga, "yoga"),
+        ];
+
+        let selected_text = match &rfa.chart_selection {
+            ChartSelection::All => rfa
+                .menu_names
+                .get("all_skills")
+                .unwrap_or(&default_value)
+                .clone(),
+            ChartSelection::SkillType(skill_type) => skill_types
+                .iter()
+                .find(|(t, _)| t == skill_type)
+                .and_then(|(_, key)| rfa.menu_names.get(*key))
+                .unwrap_or(&default_value)
+                .clone(),
+            ChartSelection::Skill(name) => rfa
+                .skills
+                .iter()
+                .find(|s| s.name == *name)
+                .and_then(|s| rfa.skill_names.get(s))
+                .unwrap_or(&default_value)
+                .clone(),
+        };
 
-                for (i, skill) in rfa.skills.iter().enumerate() {
-                    let color = match skill.skill_type {
-                        SkillTypes::Arms => ARMS_COLOR,
-                        SkillTypes::Core => CORE_COLOR,
-                        SkillTypes::Legs => LEGS_COLOR,
-                        SkillTypes::Yoga => YOGA_COLOR,
-                    };
-                    ui.label(
-                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
-                            .color(color),
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source("chart_selection")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut rfa.chart_selection,
+                        ChartSelection::All,
+                        rfa.menu_names.get("all_skills").unwrap_or(&default_value),
                     );
-                    ui.label(RichText::new(skill.completed_reps.to_string()).color(color));
-                    ui.text_edit_singleline(&mut rfa.input_reps[i])
-                        .on_hover_text(format!(
-                            "{}: {}",
-                            rfa.menu_names
-                                .get("enter_total_reps")
-                                .unwrap_or(&"Please enter the total amount of reps for".to_owned()),
-                            rfa.skill_names.get(skill).unwrap_or(&"".into())
-                        ));
-
-                    ui.end_row();
-                }
-            });
+                    for (skill_type, key) in &skill_types {
+                        ui.selectable_value(
+                            &mut rfa.chart_selection,
+                            ChartSelection::SkillType(skill_type.clone()),
+                            rfa.menu_names.get(*key).unwrap_or(&default_value),
+                        );
+                    }
+                    for skill in &rfa.skills {
+                        ui.selectable_value(
+                            &mut rfa.chart_selection,
+                            ChartSelection::Skill(skill.name.clone()),
+                            rfa.skill_names.get(skill).unwrap_or(&default_value),
+                        );
+                    }
+                });
 
-            ui.add_space(HEADER_SIZE);
+            ui.radio_value(
+                &mut rfa.chart_bucket,
+                Bucket::Day,
+                rfa.menu_names.get("day").unwrap_or(&"Day".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.chart_bucket,
+                Bucket::Week,
+                rfa.menu_names.get("week").unwrap_or(&"Week".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.chart_bucket,
+                Bucket::Month,
+                rfa.menu_names.get("month").unwrap_or(&"Month".to_owned()),
+            );
+        });
+        ui.add_space(HEADER_SIZE);
 
-            if ui
-                .button(
-                    rfa.menu_names
+        let color = match &rfa.chart_selection {
+            ChartSelection::All => Color32::LIGHT_GRAY,
+            ChartSelection::SkillType(skill_type) => match skill_type {
+                SkillTypes::Arms => ARMS_COLOR,
+                SkillTypes::Core => CORE_COLOR,
+                SkillTypes::Legs => LEGS_COLOR,
+                SkillTypes::Yoga => YOGA_COLOR,
+            },
+            ChartSelection::Skill(name) => {
+                match rfa
+                    .skills
+                    .iter()
+                    .find(|s| s.name == *name)
+                    .map(|s| &s.skill_type)
+                {
+                    Some(SkillTypes::Arms) => ARMS_COLOR,
+                    Some(SkillTypes::Core) => CORE_COLOR,
+                    Some(SkillTypes::Legs) => LEGS_COLOR,
+                    Some(SkillTypes::Yoga) => YOGA_COLOR,
+                    None => Color32::LIGHT_GRAY,
+                }
+            }
+        };
+
+        // The x axis is the number of days since the common era, so we can show it as a date.
+        // The formatters have to own their locale, so every one of them gets a copy.
+        let format_date = |locale: &Locale, x: f64| match NaiveDate::from_num_days_from_ce_opt(
+            x.round() as i32,
+        ) {
+            Some(date) => locale.format_date(date),
+            None => String::new(),
+        };
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("cumulative_reps")
+                    .unwrap_or(&"Total reps".to_owned()),
+            )
+            .size(HEADER_SIZE),
+        );
+        Plot::new("reps_chart")
+            .height(ui.available_height() / 2.0 - HEADER_SIZE)
+            .include_y(0.0)
+            .x_axis_formatter({
+                let locale = rfa.locale.clone();
+                move |x, _| format_date(&locale, x)
+            })
+            .label_formatter({
+                let locale = rfa.locale.clone();
+                move |_, point| {
+                    format!(
+                        "{}\n{}",
+                        format_date(&locale, point.x),
+                        locale.format_decimal(point.y, 0)
+                    )
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(PlotPoints::from(
+                        points
+                            .iter()
+                            .map(|p| [p.date.num_days_from_ce() as f64, p.reps as f64])
+                            .collect::<Vec<[f64; 2]>>(),
+                    ))
+                    .color(color),
+                );
+            });
+        ui.add_space(HEADER_SIZE);
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("title_progress")
+                    .unwrap_or(&"Title progress".to_owned()),
+            )
+            .size(HEADER_SIZE),
+        );
+        Plot::new("title_chart")
+            .include_y(0.0)
+            .include_y(100.0)
+            .x_axis_formatter({
+                let locale = rfa.locale.clone();
+                move |x, _| format_date(&locale, x)
+            })
+            .y_axis_formatter({
+                let locale = rfa.locale.clone();
+                move |y, _| locale.format_percent(y, 0)
+            })
+            .label_formatter({
+                let locale = rfa.locale.clone();
+                move |_, point| {
+                    format!(
+                        "{}\n{}",
+                        format_date(&locale, point.x),
+                        locale.format_percent(point.y, 2)
+                    )
+                }
+            })
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new(PlotPoints::from(
+                    points
+                        .iter()
+                        .map(|p| [p.date.num_days_from_ce() as f64, p.title_percent])
+                        .collect::<Vec<[f64; 2]>>(),
+                )));
+            });
+    });
+}
+
+pub fn stats(rfa: &mut RingFitApp, ctx: &Context) {
+    let stats = get_stats(
+        &get_workouts_from_db(&rfa.db_connection),
+        rfa.day_start_hour,
+        get_training_day(Local::now(), rfa.day_start_hour),
+    );
+    let monthly_reps = match get_monthly_reps(&rfa.db_connection) {
+        Ok(monthly_reps) => monthly_reps,
+        Err(e) => {
+            rfa.error_message = Some(e.to_string());
+            rfa.menu = None;
+            return;
+        }
+    };
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("day_start_hour")
+                    .unwrap_or(&"A new day starts at".to_owned()),
+            );
+            if ui
+                .add(
+                    DragValue::new(&mut rfa.day_start_hour)
+                        .clamp_range(0..=23)
+                        .suffix(":00"),
+                )
+                .changed()
+            {
+                let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+                settings.day_start_hour = rfa.day_start_hour;
+                if let Err(e) = save_settings(&rfa.data_dir, &settings) {
+                    rfa.error_message = Some(e.to_string());
+                }
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        Grid::new("stats").striped(true).show(ui, |ui| {
+            let locale = &rfa.locale;
+            let rows = vec![
+                (
+                    "current_streak",
+                    locale.format_number(stats.current_streak as i64),
+                ),
+                (
+                    "longest_streak",
+                    locale.format_number(stats.longest_streak as i64),
+                ),
+                ("workouts", locale.format_number(stats.workouts as i64)),
+                (
+                    "workout_days",
+                    locale.format_number(stats.workout_days as i64),
+                ),
+                ("rest_days", locale.format_number(stats.rest_days as i64)),
+                (
+                    "workouts_per_week",
+                    locale.format_decimal(stats.workouts_per_week, 1),
+                ),
+                ("average_reps", locale.format_decimal(stats.average_reps, 0)),
+            ];
+
+            for (key, value) in rows {
+                ui.label(
+                    RichText::new(rfa.menu_names.get(key).unwrap_or(&"Invalid".to_owned()))
+                        .size(HEADER_SIZE),
+                );
+                ui.label(RichText::new(value).size(HEADER_SIZE).strong());
+                ui.end_row();
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("reps_per_month")
+                    .unwrap_or(&"Reps per month".to_owned()),
+            )
+            .size(HEADER_SIZE),
+        );
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("monthly_reps").striped(true).show(ui, |ui| {
+                let default_name = "Invalid".to_owned();
+
+                // The month is only written once for all of its skills.
+                let mut last_month = None;
+                for (month, name, reps) in &monthly_reps {
+                    if last_month == Some(month) {
+                        ui.label("");
+                    } else {
+                        ui.label(month);
+                        last_month = Some(month);
+                    }
+                    ui.label(
+                        rfa.skills
+                            .iter()
+                            .find(|s| &s.name == name)
+                            .and_then(|s| rfa.skill_names.get(s))
+                            .unwrap_or(&default_name),
+                    );
+                    ui.label(rfa.locale.format_number(*reps as i64));
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+pub fn calendar(rfa: &mut RingFitApp, ctx: &Context) {
+    let workouts = get_workouts_from_db(&rfa.db_connection);
+    let daily_reps = get_daily_reps(&workouts, rfa.day_start_hour);
+
+    // The colors get brighter the more reps were done, compared to the best day of the month.
+    let max_reps = daily_reps
+        .iter()
+        .filter(|(day, _)| day.year() == rfa.calendar_month.year())
+        .filter(|(day, _)| day.month() == rfa.calendar_month.month())
+        .map(|(_, reps)| *reps)
+        .max()
+        .unwrap_or(0);
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            if ui.button("⬅").clicked() {
+                rfa.calendar_month = (rfa.calendar_month - Duration::days(1))
+                    .with_day(1)
+                    .unwrap_or(rfa.calendar_month);
+            }
+            ui.label(RichText::new(rfa.locale.format_month(rfa.calendar_month)).size(HEADER_SIZE));
+            if ui.button("➡").clicked() {
+                // From the first of a month, 31 days later is always in the next month.
+                rfa.calendar_month = (rfa.calendar_month + Duration::days(31))
+                    .with_day(1)
+                    .unwrap_or(rfa.calendar_month);
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        Grid::new("calendar").show(ui, |ui| {
+            let default_value = "Invalid".to_owned();
+
+            for key in ["mon", "tue", "wed", "thu", "fri", "sat", "sun"] {
+                ui.label(RichText::new(rfa.menu_names.get(key).unwrap_or(&default_value)).strong());
+            }
+            ui.end_row();
+
+            // The weeks start on monday, so we leave the days before the first one empty.
+            for _ in 0..rfa.calendar_month.weekday().num_days_from_monday() {
+                ui.label("");
+            }
+
+            let mut day = rfa.calendar_month;
+            while day.month() == rfa.calendar_month.month() {
+                let reps = daily_reps.get(&day).copied().unwrap_or(0);
+                let intensity = if max_reps == 0 {
+                    0.0
+                } else {
+                    reps as f32 / max_reps as f32
+                };
+                let color = match reps {
+                    0 => Color32::from_gray(40),
+                    _ => Color32::from_rgb(
+                        (30.0 + 60.0 * intensity) as u8,
+                        (70.0 + 131.0 * intensity) as u8,
+                        (10.0 + 10.0 * intensity) as u8,
+                    ),
+                };
+
+                let mut text = RichText::new(day.day().to_string());
+                if rfa.menu == Some(Menu::Calendar(Some(day))) {
+                    text = text.strong().underline();
+                }
+
+                if ui
+                    .add(Button::new(text).fill(color))
+                    .on_hover_text(format!(
+                        "{}: {}",
+                        rfa.menu_names.get("reps").unwrap_or(&default_value),
+                        rfa.locale.format_number(reps as i64)
+                    ))
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::Calendar(Some(day)));
+                }
+
+                if day.weekday() == Weekday::Sun {
+                    ui.end_row();
+                }
+                day = day.succ();
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        let selected_day = match rfa.menu {
+            Some(Menu::Calendar(Some(day))) => day,
+            _ => return,
+        };
+
+        ui.label(RichText::new(rfa.locale.format_date(selected_day)).size(HEADER_SIZE));
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("calendar_workouts").striped(true).show(ui, |ui| {
+                // The history is newest first, but a single day reads better from the morning on.
+                for (time, workout) in workouts
+                    .iter()
+                    .rev()
+                    .filter(|(time, _)| get_training_day(*time, rfa.day_start_hour) == selected_day)
+                {
+                    ui.label(rfa.locale.format_time(time));
+
+                    ui.vertical(|ui| {
+                        for (skill, _) in &workout.skill {
+                            let color = match skill.skill_type {
+                                SkillTypes::Arms => ARMS_COLOR,
+                                SkillTypes::Core => CORE_COLOR,
+                                SkillTypes::Legs => LEGS_COLOR,
+                                SkillTypes::Yoga => YOGA_COLOR,
+                            };
+                            ui.add(
+                                Label::new(
+                                    RichText::new(
+                                        rfa.skill_names.get(skill).unwrap_or(&"".to_owned()),
+                                    )
+                                    .color(color),
+                                )
+                                .wrap(false),
+                            );
+                        }
+                    });
+
+                    ui.vertical(|ui| {
+                        for (skill, reps) in &workout.skill {
+                            let color = match skill.skill_type {
+                                SkillTypes::Arms => ARMS_COLOR,
+                                SkillTypes::Core => CORE_COLOR,
+                                SkillTypes::Legs => LEGS_COLOR,
+                                SkillTypes::Yoga => YOGA_COLOR,
+                            };
+                            ui.add(
+                                Label::new(
+                                    RichText::new(rfa.locale.format_number(*reps as i64))
+                                        .color(color),
+                                )
+                                .wrap(false),
+                            );
+                        }
+                    });
+
+                    ui.end_row();
+                    ui.separator();
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+pub fn balance(rfa: &mut RingFitApp, ctx: &Context) {
+    let balance = get_balance(
+        &get_workouts_from_db(&rfa.db_connection),
+        rfa.balance_period.get_start(Local::now()),
+    );
+    let neglected = balance.get_neglected();
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut rfa.balance_period,
+                Period::Week,
+                rfa.menu_names
+                    .get("last_week")
+                    .unwrap_or(&"Last week".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.balance_period,
+                Period::Month,
+                rfa.menu_names
+                    .get("last_month")
+                    .unwrap_or(&"Last month".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.balance_period,
+                Period::Year,
+                rfa.menu_names
+                    .get("last_year")
+                    .unwrap_or(&"Last year".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.balance_period,
+                Period::All,
+                rfa.menu_names
+                    .get("all_time")
+                    .unwrap_or(&"All time".to_owned()),
+            );
+        });
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            let default_value = "Invalid".to_owned();
+
+            // The bars are relative to the most trained one.
+            let max_type_reps = balance
+                .skill_types
+                .iter()
+                .map(|(_, reps)| *reps)
+                .max()
+                .unwrap_or(0)
+                .max(1);
+
+            Grid::new("balance_types").striped(true).show(ui, |ui| {
+                for (skill_type, reps) in &balance.skill_types {
+                    let (color, key) = match skill_type {
+                        SkillTypes::Arms => (ARMS_COLOR, "arms"),
+                        SkillTypes::Core => (CORE_COLOR, "core"),
+                        SkillTypes::Legs => (LEGS_COLOR, "legs"),
+                        SkillTypes::Yoga => (YOGA_COLOR, "yoga"),
+                    };
+                    ui.label(
+                        RichText::new(rfa.menu_names.get(key).unwrap_or(&default_value))
+                            .color(color),
+                    );
+                    ui.add(
+                        ProgressBar::new(*reps as f32 / max_type_reps as f32)
+                            .text(rfa.locale.format_number(*reps as i64)),
+                    );
+                    ui.end_row();
+                }
+            });
+            ui.add_space(HEADER_SIZE);
+
+            let max_hashtag_reps = balance
+                .hashtags
+                .iter()
+                .map(|(_, reps)| *reps)
+                .max()
+                .unwrap_or(0)
+                .max(1);
+
+            Grid::new("balance_hashtags").striped(true).show(ui, |ui| {
+                for (hashtag, reps) in &balance.hashtags {
+                    let mut name =
+                        RichText::new(rfa.hashtag_names.get(hashtag).unwrap_or(&default_value));
+                    if neglected.contains(hashtag) {
+                        name = name.color(CANCEL_COLOR);
+                    }
+                    ui.label(name);
+                    ui.add(
+                        ProgressBar::new(*reps as f32 / max_hashtag_reps as f32)
+                            .text(rfa.locale.format_number(*reps as i64)),
+                    );
+                    ui.end_row();
+                }
+            });
+            ui.add_space(HEADER_SIZE);
+
+            if !neglected.is_empty() {
+                ui.label(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("neglected")
+                            .unwrap_or(&"These muscle groups could use more training".to_owned()),
+                    )
+                    .size(HEADER_SIZE),
+                );
+                ui.label(
+                    RichText::new(
+                        neglected
+                            .iter()
+                            .map(|h| rfa.hashtag_names.get(h).unwrap_or(&default_value).clone())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    )
+                    .color(CANCEL_COLOR),
+                );
+            }
+        });
+    });
+}
+
+pub fn schedule(rfa: &mut RingFitApp, ctx: &Context) {
+    let today = get_training_day(Local::now(), rfa.day_start_hour);
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        let default_value = "Invalid".to_owned();
+
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("target_date")
+                    .unwrap_or(&"Finish every goal on".to_owned()),
+            );
+            ui.add(DatePickerButton::new(&mut rfa.schedule_date));
+        });
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("rest_days")
+                    .unwrap_or(&"Rest days".to_owned()),
+            );
+            for (day, key) in [
+                (Weekday::Mon, "mon"),
+                (Weekday::Tue, "tue"),
+                (Weekday::Wed, "wed"),
+                (Weekday::Thu, "thu"),
+                (Weekday::Fri, "fri"),
+                (Weekday::Sat, "sat"),
+                (Weekday::Sun, "sun"),
+            ] {
+                let mut checked = rfa.schedule_rest_days.contains(&day);
+                if ui
+                    .checkbox(
+                        &mut checked,
+                        rfa.menu_names.get(key).unwrap_or(&default_value),
+                    )
+                    .changed()
+                {
+                    if checked {
+                        rfa.schedule_rest_days.push(day);
+                    } else {
+                        rfa.schedule_rest_days.retain(|d| *d != day);
+                    }
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            // Every day being a rest day leaves no days to train on.
+            if ui
+                .add_enabled(
+                    rfa.schedule_rest_days.len() < 7,
+                    Button::new(
+                        rfa.menu_names
+                            .get("save_schedule")
+                            .unwrap_or(&"Make schedule".to_owned()),
+                    ),
+                )
+                .clicked()
+            {
+                match Schedule::new(
+                    &rfa.skills,
+                    today,
+                    rfa.schedule_date.naive_utc(),
+                    rfa.schedule_rest_days.clone(),
+                ) {
+                    Ok(schedule) => {
+                        rfa.schedule = Some(schedule);
+                        let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+                        settings.schedule = rfa.schedule.clone();
+                        if let Err(e) = save_settings(&rfa.data_dir, &settings) {
+                            rfa.error_message = Some(e.to_string());
+                        }
+                    }
+                    Err(e) => rfa.error_message = Some(e.to_string()),
+                }
+            }
+            if rfa.schedule.is_some()
+                && ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("remove_schedule")
+                                .unwrap_or(&"Remove schedule".to_owned()),
+                        )
+                        .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+            {
+                rfa.schedule = None;
+                let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+                settings.schedule = None;
+                if let Err(e) = save_settings(&rfa.data_dir, &settings) {
+                    rfa.error_message = Some(e.to_string());
+                }
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        let schedule = match &rfa.schedule {
+            Some(schedule) => schedule,
+            None => return,
+        };
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("schedule").striped(true).show(ui, |ui| {
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("daily_reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("weekly_reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("ahead_behind").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for skill in &rfa.skills {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(
+                            rfa.locale
+                                .format_number(schedule.get_daily_reps(skill, today) as i64),
+                        )
+                        .color(color),
+                    );
+                    ui.label(
+                        RichText::new(
+                            rfa.locale
+                                .format_number(schedule.get_weekly_reps(skill, today) as i64),
+                        )
+                        .color(color),
+                    );
+
+                    let difference = schedule.get_difference(skill, today);
+                    ui.label(
+                        RichText::new(rfa.locale.format_difference(difference)).color(
+                            if difference >= 0 {
+                                CONFIRM_COLOR
+                            } else {
+                                CANCEL_COLOR
+                            },
+                        ),
+                    );
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+pub fn set_reps(rfa: &mut RingFitApp, ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("set_reps").striped(true).show(ui, |ui| {
+                let default_value = "Invalid".to_owned();
+
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("new_reps").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for (i, skill) in rfa.skills.iter().enumerate() {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.completed_reps as i64))
+                            .color(color),
+                    );
+                    ui.text_edit_singleline(&mut rfa.input_reps[i])
+                        .on_hover_text(format!(
+                            "{}: {}",
+                            rfa.menu_names
+                                .get("enter_total_reps")
+                                .unwrap_or(&"Please enter the total amount of reps for".to_owned()),
+                            rfa.skill_names.get(skill).unwrap_or(&"".into())
+                        ));
+
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(
+                    rfa.menu_names
                         .get("save_reps")
                         .unwrap_or(&"Save reps".to_owned()),
                 )
                 .clicked()
             {
-                rfa.menu = Some(Menu::SetReps(true));
+                rfa.menu = Some(Menu::SetReps(true));
+            }
+        });
+
+        if rfa.menu == Some(Menu::SetReps(true)) {
+            Window::new(
+                rfa.menu_names
+                    .get("confirm_reps")
+                    .unwrap_or(&"Confirm Reps".to_owned()),
+            )
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("show_set_reps")
+                            .unwrap_or(&"Set these reps?".to_owned()),
+                    )
+                    .size(HEADER_SIZE),
+                );
+                ui.add_space(HEADER_SIZE);
+
+                for (i, skill) in rfa.skills.iter().enumerate() {
+                    if !rfa.input_reps[i].is_empty() && rfa.input_reps[i].parse::<usize>().is_ok() {
+                        let color = match skill.skill_type {
+                            SkillTypes::Arms => ARMS_COLOR,
+                            SkillTypes::Core => CORE_COLOR,
+                            SkillTypes::Legs => LEGS_COLOR,
+                            SkillTypes::Yoga => YOGA_COLOR,
+                        };
+                        ui.label(
+                            RichText::new(format!(
+                                "{}: {} ➡ {}",
+                                rfa.skill_names.get(skill).unwrap_or(&"".into()),
+                                skill.completed_reps,
+                                rfa.input_reps[i]
+                            ))
+                            .color(color),
+                        );
+                    }
+                }
+                ui.add_space(HEADER_SIZE);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(
+                            RichText::new(
+                                rfa.menu_names
+                                    .get("confirm")
+                                    .unwrap_or(&"Confirm".to_owned()),
+                            )
+                            .color(CONFIRM_COLOR),
+                        )
+                        .clicked()
+                    {
+                        if rfa.auto_backup() {
+                            // Every change gets recorded in the history as a manual adjustment.
+                            if let Err(e) = save_manual_reps_to_db(
+                                &rfa.db_connection,
+                                &rfa.skills,
+                                &rfa.input_reps,
+                            ) {
+                                rfa.error_message = Some(e.to_string());
+                                rfa.menu = Some(Menu::SetReps(false));
+                                return;
+                            }
+
+                            let all_skills = Skill::get_all_skills(&rfa.db_connection);
+
+                            rfa.input_reps = vec!["".into(); all_skills.len()];
+                            rfa.skills = all_skills;
+                        }
+                        rfa.menu = Some(Menu::SetReps(false));
+                    }
+
+                    if ui
+                        .button(
+                            RichText::new(
+                                rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()),
+                            )
+                            .color(CANCEL_COLOR),
+                        )
+                        .clicked()
+                    {
+                        rfa.menu = Some(Menu::SetReps(false));
+                    }
+                });
+            });
+        }
+    });
+}
+
+pub fn goals(rfa: &mut RingFitApp, ctx: &Context) {
+    let profiles = get_goal_profiles(&rfa.db_connection).unwrap_or_default();
+    let active_profile = get_active_profile(&rfa.db_connection).unwrap_or_default();
+
+    // Every change to the goals ends here, the percentages everywhere else are calculated from the skills.
+    let mut result: Option<Result<(), Box<dyn Error>>> = None;
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        let default_value = "Invalid".to_owned();
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("goal_profiles")
+                    .unwrap_or(&"Goal profiles".to_owned()),
+            )
+            .size(HEADER_SIZE),
+        );
+        Grid::new("goal_profiles").striped(true).show(ui, |ui| {
+            for profile in &profiles {
+                if active_profile.as_ref() == Some(profile) {
+                    ui.label(RichText::new(profile).strong().color(CONFIRM_COLOR));
+                } else {
+                    ui.label(profile);
+                }
+                if ui
+                    .button(rfa.menu_names.get("use").unwrap_or(&"Use".to_owned()))
+                    .clicked()
+                {
+                    result = Some(apply_goal_profile(&rfa.db_connection, profile));
+                }
+                // The built-in profiles can't be made again, so they can't be deleted.
+                if !is_built_in_profile(profile)
+                    && ui
+                        .button(
+                            RichText::new(
+                                rfa.menu_names.get("delete").unwrap_or(&"Delete".to_owned()),
+                            )
+                            .color(CANCEL_COLOR),
+                        )
+                        .clicked()
+                {
+                    result = Some(delete_goal_profile(&rfa.db_connection, profile));
+                }
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut rfa.goal_profile_name);
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("save_profile")
+                        .unwrap_or(&"Save current goals as profile".to_owned()),
+                )
+                .clicked()
+            {
+                result = Some(save_goal_profile(
+                    &rfa.db_connection,
+                    &rfa.goal_profile_name,
+                ));
+                rfa.goal_profile_name.clear();
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            let skill_types = [
+                (None, "all_skills"),
+                (Some(SkillTypes::Arms), "arms"),
+                (Some(SkillTypes::Core), "core"),
+                (Some(SkillTypes::Legs), "legs"),
+                (Some(SkillTypes::Yoga), "yoga"),
+            ];
+
+            ComboBox::from_id_source("goal_type")
+                .selected_text(
+                    skill_types
+                        .iter()
+                        .find(|(t, _)| *t == rfa.goal_type)
+                        .and_then(|(_, key)| rfa.menu_names.get(*key))
+                        .unwrap_or(&default_value),
+                )
+                .show_ui(ui, |ui| {
+                    for (skill_type, key) in &skill_types {
+                        ui.selectable_value(
+                            &mut rfa.goal_type,
+                            skill_type.clone(),
+                            rfa.menu_names.get(*key).unwrap_or(&default_value),
+                        );
+                    }
+                });
+            ui.add(DragValue::new(&mut rfa.goal_amount).clamp_range(1..=1_000_000));
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("set_goals")
+                        .unwrap_or(&"Set goals".to_owned()),
+                )
+                .clicked()
+            {
+                result = Some(set_goals_for_type(
+                    &rfa.db_connection,
+                    rfa.goal_type.as_ref(),
+                    rfa.goal_amount,
+                ));
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("goals").striped(true).show(ui, |ui| {
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("goal").unwrap_or(&default_value),
+                    rfa.menu_names.get("new_goal").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for (i, skill) in rfa.skills.iter().enumerate() {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.completed_reps as i64))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(skill.goal_reps as i64))
+                            .color(color),
+                    );
+                    ui.text_edit_singleline(&mut rfa.input_goals[i]);
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("save_goals")
+                        .unwrap_or(&"Save goals".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.menu = Some(Menu::Goals(true));
+            }
+        });
+
+        if rfa.menu == Some(Menu::Goals(true)) {
+            Window::new(
+                rfa.menu_names
+                    .get("confirm_goals")
+                    .unwrap_or(&"Confirm Goals".to_owned()),
+            )
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("show_set_goals")
+                            .unwrap_or(&"Set these goals?".to_owned()),
+                    )
+                    .size(HEADER_SIZE),
+                );
+                ui.add_space(HEADER_SIZE);
+
+                // The same goals `save_goals_to_db` would save.
+                for (i, skill) in rfa.skills.iter().enumerate() {
+                    if matches!(rfa.input_goals[i].parse::<usize>(), Ok(goal) if goal > 0) {
+                        let color = match skill.skill_type {
+                            SkillTypes::Arms => ARMS_COLOR,
+                            SkillTypes::Core => CORE_COLOR,
+                            SkillTypes::Legs => LEGS_COLOR,
+                            SkillTypes::Yoga => YOGA_COLOR,
+                        };
+                        ui.label(
+                            RichText::new(format!(
+                                "{}: {} ➡ {}",
+                                rfa.skill_names.get(skill).unwrap_or(&"".into()),
+                                skill.goal_reps,
+                                rfa.input_goals[i]
+                            ))
+                            .color(color),
+                        );
+                    }
+                }
+                ui.add_space(HEADER_SIZE);
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(
+                            RichText::new(
+                                rfa.menu_names
+                                    .get("confirm")
+                                    .unwrap_or(&"Confirm".to_owned()),
+                            )
+                            .color(CONFIRM_COLOR),
+                        )
+                        .clicked()
+                    {
+                        result = Some(save_goals_to_db(
+                            &rfa.db_connection,
+                            &rfa.skills,
+                            &rfa.input_goals,
+                        ));
+                        rfa.input_goals = vec!["".into(); rfa.skills.len()];
+                        rfa.menu = Some(Menu::Goals(false));
+                    }
+
+                    if ui
+                        .button(
+                            RichText::new(
+                                rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()),
+                            )
+                            .color(CANCEL_COLOR),
+                        )
+                        .clicked()
+                    {
+                        rfa.menu = Some(Menu::Goals(false));
+                    }
+                });
+            });
+        }
+    });
+
+    match result {
+        Some(Ok(())) => rfa.skills = Skill::get_all_skills(&rfa.db_connection),
+        Some(Err(e)) => rfa.error_message = Some(e.to_string()),
+        None => (),
+    }
+}
+
+pub fn custom_skills(rfa: &mut RingFitApp, ctx: &Context) {
+    let mut result: Option<Result<(), Box<dyn Error>>> = None;
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        let default_value = "Invalid".to_owned();
+        let skill_types = [
+            (SkillTypes::Arms, "arms"),
+            (SkillTypes::Core, "core"),
+            (SkillTypes::Legs, "legs"),
+            (SkillTypes::Yoga, "yoga"),
+        ];
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("custom_skills")
+                    .unwrap_or(&"Custom skills".to_owned()),
+            )
+            .size(HEADER_SIZE),
+        );
+        Grid::new("custom_skills").striped(true).show(ui, |ui| {
+            for skill in rfa.skills.iter().filter(|s| s.custom) {
+                let color = match skill.skill_type {
+                    SkillTypes::Arms => ARMS_COLOR,
+                    SkillTypes::Core => CORE_COLOR,
+                    SkillTypes::Legs => LEGS_COLOR,
+                    SkillTypes::Yoga => YOGA_COLOR,
+                };
+                ui.label(RichText::new(&skill.name).color(color));
+                ui.label(
+                    skill
+                        .hashtags
+                        .iter()
+                        .filter(|h| **h != SkillHashtags::Empty)
+                        .map(|h| rfa.hashtag_names.get(h).unwrap_or(&default_value).as_str())
+                        .collect::<Vec<&str>>()
+                        .join(" "),
+                );
+                ui.label(rfa.locale.format_number(skill.goal_reps as i64));
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("delete").unwrap_or(&"Delete".to_owned()))
+                            .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+                {
+                    result = Some(delete_custom_skill(&rfa.db_connection, skill));
+                }
+                ui.end_row();
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        Grid::new("add_custom_skill").show(ui, |ui| {
+            ui.label(rfa.menu_names.get("name").unwrap_or(&default_value));
+            ui.text_edit_singleline(&mut rfa.custom_skill_name);
+            ui.end_row();
+
+            ui.label(rfa.menu_names.get("skill_type").unwrap_or(&default_value));
+            ComboBox::from_id_source("custom_skill_type")
+                .selected_text(
+                    skill_types
+                        .iter()
+                        .find(|(t, _)| *t == rfa.custom_skill_type)
+                        .and_then(|(_, key)| rfa.menu_names.get(*key))
+                        .unwrap_or(&default_value),
+                )
+                .show_ui(ui, |ui| {
+                    for (skill_type, key) in &skill_types {
+                        ui.selectable_value(
+                            &mut rfa.custom_skill_type,
+                            skill_type.clone(),
+                            rfa.menu_names.get(*key).unwrap_or(&default_value),
+                        );
+                    }
+                });
+            ui.end_row();
+
+            ui.label(rfa.menu_names.get("hashtags").unwrap_or(&default_value));
+            ui.horizontal(|ui| {
+                for (i, selected) in rfa.custom_skill_hashtags.iter_mut().enumerate() {
+                    ComboBox::from_id_source(("custom_skill_hashtag", i))
+                        .selected_text(rfa.hashtag_names.get(selected).unwrap_or(&default_value))
+                        .show_ui(ui, |ui| {
+                            for hashtag in SkillHashtags::get_all_hashtags() {
+                                let name = rfa
+                                    .hashtag_names
+                                    .get(&hashtag)
+                                    .unwrap_or(&default_value)
+                                    .clone();
+                                ui.selectable_value(selected, hashtag, name);
+                            }
+                        });
+                }
+            });
+            ui.end_row();
+
+            ui.label(rfa.menu_names.get("goal").unwrap_or(&default_value))
+                .on_hover_text(
+                    rfa.menu_names
+                        .get("show_custom_goal")
+                        .unwrap_or(&"Leave this empty to use the default goal".to_owned()),
+                );
+            ui.text_edit_singleline(&mut rfa.custom_skill_goal);
+            ui.end_row();
+        });
+
+        if ui
+            .button(
+                rfa.menu_names
+                    .get("add_skill")
+                    .unwrap_or(&"Add skill".to_owned()),
+            )
+            .clicked()
+        {
+            let goal = rfa.custom_skill_goal.trim();
+            result = Some(
+                if goal.is_empty() {
+                    Ok(None)
+                } else {
+                    goal.parse::<usize>()
+                        .map(Some)
+                        .map_err(|_| format!("Invalid goal: {}", goal).into())
+                }
+                .and_then(|goal_reps| {
+                    let skill = Skill::new_custom(
+                        &rfa.custom_skill_name,
+                        rfa.custom_skill_type.clone(),
+                        rfa.custom_skill_hashtags.clone(),
+                        goal_reps,
+                    );
+                    add_custom_skill(&rfa.db_connection, &skill)
+                }),
+            );
+        }
+    });
+
+    match result {
+        Some(Ok(())) => {
+            rfa.custom_skill_name.clear();
+            rfa.custom_skill_goal.clear();
+            rfa.reload_skills();
+        }
+        Some(Err(e)) => rfa.error_message = Some(e.to_string()),
+        None => (),
+    }
+}
+
+pub fn check_totals(rfa: &mut RingFitApp, ctx: &Context) {
+    // This runs every frame, so on an error we go back instead of showing it again and again.
+    let discrepancies = match check_rep_totals(&rfa.db_connection) {
+        Ok(discrepancies) => discrepancies,
+        Err(e) => {
+            rfa.error_message = Some(e.to_string());
+            rfa.menu = None;
+            return;
+        }
+    };
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        if discrepancies.is_empty() {
+            ui.label(
+                rfa.menu_names
+                    .get("totals_match")
+                    .unwrap_or(&"All totals match the workout history.".to_owned()),
+            );
+            return;
+        }
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("check_totals").striped(true).show(ui, |ui| {
+                let default_value = "Invalid".to_owned();
+
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("history_reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("difference").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for discrepancy in &discrepancies {
+                    let color = match discrepancy.skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(
+                            rfa.skill_names
+                                .get(&discrepancy.skill)
+                                .unwrap_or(&"".to_owned()),
+                        )
+                        .color(color),
+                    );
+                    ui.label(
+                        RichText::new(
+                            rfa.locale
+                                .format_number(discrepancy.skill.completed_reps as i64),
+                        )
+                        .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(discrepancy.history_reps))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_difference(discrepancy.get_difference()))
+                            .color(color),
+                    );
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("reconcile")
+                        .unwrap_or(&"Record manual adjustments".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.menu = Some(Menu::CheckTotals(true));
+            }
+        });
+    });
+
+    if rfa.menu == Some(Menu::CheckTotals(true)) {
+        Window::new(
+            rfa.menu_names
+                .get("confirm_reconcile")
+                .unwrap_or(&"Confirm Adjustments".to_owned()),
+        )
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("show_reconcile")
+                    .unwrap_or(&"Record these differences as manual adjustments?".to_owned()),
+            );
+            ui.add_space(HEADER_SIZE);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("confirm")
+                                .unwrap_or(&"Confirm".to_owned()),
+                        )
+                        .color(CONFIRM_COLOR),
+                    )
+                    .clicked()
+                {
+                    if let Err(e) = reconcile_rep_totals(&rfa.db_connection, &discrepancies) {
+                        rfa.error_message = Some(e.to_string());
+                    }
+
+                    rfa.menu = Some(Menu::CheckTotals(false));
+                }
+
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
+                            .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::CheckTotals(false));
+                }
+            });
+        });
+    }
+}
+
+pub fn view_skills(rfa: &mut RingFitApp, ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("view_skills").striped(true).show(ui, |ui| {
+                let default_name = "Invalid".to_owned();
+
+                let headers = vec![
+                    rfa.menu_names.get("name").unwrap_or(&default_name),
+                    rfa.menu_names.get("hits").unwrap_or(&default_name),
+                    rfa.menu_names.get("level").unwrap_or(&default_name),
+                    rfa.menu_names.get("damage").unwrap_or(&default_name),
+                    rfa.menu_names.get("unlocks").unwrap_or(&default_name),
+                    rfa.menu_names.get("cooldown").unwrap_or(&default_name),
+                    rfa.menu_names.get("hashtags").unwrap_or(&default_name),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                // Custom skills are not in the game, so there is nothing to show about them.
+                for (i, skill) in rfa.skills.iter().filter(|s| !s.custom).enumerate() {
+                    ui.label(
+                        RichText::new(format!(
+                            "{}) {}",
+                            i + 1,
+                            rfa.skill_names.get(skill).unwrap_or(&default_name)
+                        ))
+                        .color(match skill.skill_type {
+                            SkillTypes::Arms => ARMS_COLOR,
+                            SkillTypes::Core => CORE_COLOR,
+                            SkillTypes::Legs => LEGS_COLOR,
+                            SkillTypes::Yoga => YOGA_COLOR,
+                        }),
+                    );
+                    ui.label(
+                        RichText::new(match skill.hits {
+                            SkillHits::One => "    🎯    ",
+                            SkillHits::Three => "  🎯🎯🎯  ",
+                            SkillHits::Five => "🎯🎯🎯🎯🎯",
+                            SkillHits::Heal => "    ❤    ",
+                        })
+                        .font(FontId::monospace(14.)),
+                    );
+
+                    // Without a level there is nothing to grey out.
+                    let player_level = rfa.player_level.unwrap_or(MAX_PLAYER_LEVEL);
+                    let tier_text = |tier: usize, text: String| {
+                        let text = RichText::new(text);
+                        if skill.is_tier_unlocked(tier, player_level) {
+                            text
+                        } else {
+                            text.weak()
+                        }
+                    };
+
+                    ui.vertical(|ui| {
+                        for tier in 1..=MAX_LEVEL {
+                            ui.label(tier_text(tier, tier.to_string()));
+                        }
+                    });
+
+                    ui.vertical(|ui| {
+                        for (i, dmg) in skill.damage.iter().enumerate() {
+                            ui.label(tier_text(i + 1, dmg.to_string()));
+                        }
+                    });
+
+                    ui.vertical(|ui| {
+                        for (i, unlock) in skill.unlocks.iter().enumerate() {
+                            ui.label(tier_text(i + 1, unlock.to_string()));
+                        }
+                    });
+
+                    ui.vertical(|ui| {
+                        for (i, time) in skill.recharge_time.iter().enumerate() {
+                            ui.label(tier_text(i + 1, time.to_string()));
+                        }
+                    });
+
+                    ui.vertical(|ui| {
+                        for hashtag in &skill.hashtags {
+                            ui.add(
+                                Label::new(RichText::new(match hashtag {
+                                    SkillHashtags::Empty => "",
+                                    _ => rfa.hashtag_names.get(hashtag).unwrap_or(&default_name),
+                                }))
+                                .wrap(false),
+                            );
+                        }
+                    });
+
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+/// Records the Adventure Mode level of the player, and shows which skills are unlocked at it and which come next.
+pub fn unlocks(rfa: &mut RingFitApp, ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("player_level")
+                    .unwrap_or(&"Adventure Mode level".to_owned()),
+            );
+            ui.add(DragValue::new(&mut rfa.player_level_input).clamp_range(1..=MAX_PLAYER_LEVEL));
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("save_level")
+                        .unwrap_or(&"Save level".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.player_level = Some(rfa.player_level_input);
+
+                let mut settings = load_settings(&rfa.data_dir).unwrap_or_default();
+                settings.player_level = rfa.player_level;
+                if let Err(e) = save_settings(&rfa.data_dir, &settings) {
+                    rfa.error_message = Some(e.to_string());
+                }
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        let player_level =
+            match rfa.player_level {
+                Some(level) => level,
+                None => {
+                    ui.label(rfa.menu_names.get("show_no_level").unwrap_or(
+                        &"Save your level to see which skills are unlocked.".to_owned(),
+                    ));
+                    return;
+                }
+            };
+
+        let default_name = "Invalid".to_owned();
+        let locale = &rfa.locale;
+        let game_skills: Vec<&Skill> = rfa.skills.iter().filter(|s| !s.custom).collect();
+
+        Grid::new("unlocked").striped(true).show(ui, |ui| {
+            let rows = vec![
+                (
+                    "unlocked_skills",
+                    game_skills
+                        .iter()
+                        .filter(|s| s.get_unlocked_tiers(player_level) > 0)
+                        .count(),
+                    game_skills.len(),
+                ),
+                (
+                    "unlocked_tiers",
+                    game_skills
+                        .iter()
+                        .map(|s| s.get_unlocked_tiers(player_level))
+                        .sum(),
+                    game_skills.len() * MAX_LEVEL,
+                ),
+            ];
+
+            for (key, unlocked, total) in rows {
+                ui.label(rfa.menu_names.get(key).unwrap_or(&default_name));
+                ui.label(format!(
+                    "{} / {}",
+                    locale.format_number(unlocked as i64),
+                    locale.format_number(total as i64)
+                ));
+                ui.end_row();
+            }
+        });
+        ui.add_space(HEADER_SIZE);
+
+        let next_unlocks = get_next_unlocks(&rfa.skills, player_level);
+        match next_unlocks.first() {
+            Some(first) => {
+                ui.label(
+                    RichText::new(format!(
+                        "{}: {} {} ({} {})",
+                        rfa.menu_names.get("next_unlocks").unwrap_or(&default_name),
+                        rfa.menu_names.get("level").unwrap_or(&default_name),
+                        locale.format_number(first.level as i64),
+                        locale.format_number((first.level - player_level) as i64),
+                        rfa.menu_names.get("levels_to_go").unwrap_or(&default_name),
+                    ))
+                    .size(HEADER_SIZE),
+                );
+                for unlock in &next_unlocks {
+                    ui.label(format!(
+                        "{} {} {}",
+                        rfa.skill_names.get(&unlock.skill).unwrap_or(&default_name),
+                        rfa.menu_names.get("tier").unwrap_or(&default_name),
+                        unlock.tier
+                    ));
+                }
+            }
+            None => {
+                ui.label(
+                    rfa.menu_names
+                        .get("show_all_unlocked")
+                        .unwrap_or(&"Every skill is fully unlocked.".to_owned()),
+                );
+            }
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.label(
+            RichText::new(
+                rfa.menu_names
+                    .get("unlock_timeline")
+                    .unwrap_or(&default_name),
+            )
+            .size(HEADER_SIZE),
+        );
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("unlock_timeline").striped(true).show(ui, |ui| {
+                for key in ["level", "name", "tier"] {
+                    ui.label(rfa.menu_names.get(key).unwrap_or(&default_name));
+                }
+                ui.end_row();
+
+                let mut last_level = None;
+                for unlock in get_upcoming_unlocks(&rfa.skills, player_level) {
+                    // The level is only written once for all the tiers that unlock with it.
+                    if last_level == Some(unlock.level) {
+                        ui.label("");
+                    } else {
+                        ui.label(locale.format_number(unlock.level as i64));
+                        last_level = Some(unlock.level);
+                    }
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(&unlock.skill).unwrap_or(&default_name))
+                            .color(match unlock.skill.skill_type {
+                                SkillTypes::Arms => ARMS_COLOR,
+                                SkillTypes::Core => CORE_COLOR,
+                                SkillTypes::Legs => LEGS_COLOR,
+                                SkillTypes::Yoga => YOGA_COLOR,
+                            }),
+                    );
+                    ui.label(unlock.tier.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+    });
+}
+
+pub fn import_export(rfa: &mut RingFitApp, ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.status_message = None;
+            rfa.pending_import = None;
+            rfa.menu = None;
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ui.horizontal(|ui| {
+            ui.label(rfa.menu_names.get("file").unwrap_or(&"File".to_owned()));
+            ui.text_edit_singleline(&mut rfa.file_path);
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("export_workouts")
+                        .unwrap_or(&"Export workouts".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.status_message = Some(
+                    match std::fs::File::create(&rfa.file_path)
+                        .map_err(|e| e.into())
+                        .and_then(|mut f| export_workouts(&rfa.db_connection, &mut f))
+                    {
+                        Ok(_) => format!(
+                            "{}: {}",
+                            rfa.menu_names
+                                .get("exported")
+                                .unwrap_or(&"Saved to".to_owned()),
+                            rfa.file_path
+                        ),
+                        Err(e) => e.to_string(),
+                    },
+                );
+            }
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("export_progress")
+                        .unwrap_or(&"Export progress".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.status_message = Some(
+                    match std::fs::File::create(&rfa.file_path)
+                        .map_err(|e| e.into())
+                        .and_then(|mut f| export_progress(&rfa.db_connection, &mut f))
+                    {
+                        Ok(_) => format!(
+                            "{}: {}",
+                            rfa.menu_names
+                                .get("exported")
+                                .unwrap_or(&"Saved to".to_owned()),
+                            rfa.file_path
+                        ),
+                        Err(e) => e.to_string(),
+                    },
+                );
+            }
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("load_import")
+                        .unwrap_or(&"Load workouts to import".to_owned()),
+                )
+                .clicked()
+            {
+                match std::fs::read_to_string(&rfa.file_path)
+                    .map_err(|e| e.into())
+                    .and_then(|input| parse_workouts_csv(&rfa.db_connection, &input))
+                {
+                    Ok(workouts) => {
+                        rfa.status_message = None;
+                        rfa.pending_import = Some(workouts);
+                    }
+                    Err(e) => {
+                        rfa.status_message = Some(e.to_string());
+                        rfa.pending_import = None;
+                    }
+                }
+            }
+        });
+
+        if let Some(message) = &rfa.status_message {
+            ui.label(message);
+        }
+
+        ui.add_space(HEADER_SIZE);
+
+        let workouts = match &rfa.pending_import {
+            Some(workouts) => workouts,
+            None => return,
+        };
+
+        ui.horizontal(|ui| {
+            ui.radio_value(
+                &mut rfa.import_mode,
+                ImportMode::Merge,
+                rfa.menu_names
+                    .get("merge")
+                    .unwrap_or(&"Add to history".to_owned()),
+            );
+            ui.radio_value(
+                &mut rfa.import_mode,
+                ImportMode::Replace,
+                rfa.menu_names
+                    .get("replace")
+                    .unwrap_or(&"Replace history".to_owned()),
+            );
+        });
+
+        let preview = preview_import(&rfa.db_connection, workouts, rfa.import_mode);
+
+        ui.label(format!(
+            "{}: {}",
+            rfa.menu_names
+                .get("new_workouts")
+                .unwrap_or(&"New workouts".to_owned()),
+            preview.new_workouts
+        ));
+        ui.label(format!(
+            "{}: {}",
+            rfa.menu_names
+                .get("skipped_workouts")
+                .unwrap_or(&"Workouts already in history".to_owned()),
+            preview.skipped_workouts
+        ));
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("import_preview").striped(true).show(ui, |ui| {
+                let default_value = "Invalid".to_owned();
+
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("new_reps").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for (skill, old_reps, new_reps) in &preview.changes {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
+                            .color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(*old_reps as i64)).color(color),
+                    );
+                    ui.label(
+                        RichText::new(rfa.locale.format_number(*new_reps as i64)).color(color),
+                    );
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(rfa.menu_names.get("import").unwrap_or(&"Import".to_owned()))
+                .clicked()
+            {
+                rfa.menu = Some(Menu::ImportExport(true));
             }
         });
+    });
 
-        if rfa.menu == Some(Menu::SetReps(true)) {
-            Window::new(
+    if rfa.menu == Some(Menu::ImportExport(true)) {
+        Window::new(
+            rfa.menu_names
+                .get("confirm_import")
+                .unwrap_or(&"Confirm Import".to_owned()),
+        )
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
                 rfa.menu_names
-                    .get("confirm_reps")
-                    .unwrap_or(&"Confirm Reps".to_owned()),
-            )
-            .collapsible(false)
-            .resizable(false)
-            .show(ctx, |ui| {
-                ui.label(
-                    RichText::new(
-                        rfa.menu_names
-                            .get("show_set_reps")
-                            .unwrap_or(&"Set these reps?".to_owned()),
-                    )
-                    .size(HEADER_SIZE),
-                );
-                ui.add_space(HEADER_SIZE);
-
-                for (i, skill) in rfa.skills.iter().enumerate() {
-                    if !rfa.input_reps[i].is_empty() && rfa.input_reps[i].parse::<usize>().is_ok() {
-                        let color = match skill.skill_type {
-                            SkillTypes::Arms => ARMS_COLOR,
-                            SkillTypes::Core => CORE_COLOR,
-                            SkillTypes::Legs => LEGS_COLOR,
-                            SkillTypes::Yoga => YOGA_COLOR,
-                        };
-                        ui.label(
-                            RichText::new(format!(
-                                "{}: {} ➡ {}",
-                                rfa.skill_names.get(skill).unwrap_or(&"".into()),
-                                skill.completed_reps,
-                                rfa.input_reps[i]
-                            ))
-                            .color(color),
-                        );
-                    }
-                }
-                ui.add_space(HEADER_SIZE);
+                    .get("show_import")
+                    .unwrap_or(&"Import these workouts?".to_owned()),
+            );
+            ui.add_space(HEADER_SIZE);
 
-                ui.horizontal(|ui| {
-                    if ui
-                        .button(
-                            RichText::new(
-                                rfa.menu_names
-                                    .get("confirm")
-                                    .unwrap_or(&"Confirm".to_owned()),
-                            )
-                            .color(CONFIRM_COLOR),
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("confirm")
+                                .unwrap_or(&"Confirm".to_owned()),
                         )
-                        .clicked()
-                    {
-                        for (i, skill) in rfa.skills.iter().enumerate() {
-                            if !rfa.input_reps[i].is_empty()
-                                && rfa.input_reps[i].parse::<usize>().is_ok()
-                            {
-                                skill
-                                    .set_reps(
-                                        &rfa.db_connection,
-                                        rfa.input_reps[i].parse().unwrap_or(0),
-                                    )
-                                    .expect("Could not set reps in database.");
-                            }
+                        .color(CONFIRM_COLOR),
+                    )
+                    .clicked()
+                {
+                    // If the backup does not work, the workouts stay loaded so you can try again.
+                    if rfa.auto_backup() {
+                        if let Some(workouts) = rfa.pending_import.take() {
+                            rfa.status_message = Some(
+                                match import_workouts(
+                                    &rfa.db_connection,
+                                    &workouts,
+                                    rfa.import_mode,
+                                ) {
+                                    Ok(_) => rfa
+                                        .menu_names
+                                        .get("imported")
+                                        .unwrap_or(&"Imported the workouts".to_owned())
+                                        .clone(),
+                                    Err(e) => e.to_string(),
+                                },
+                            );
                         }
-
-                        let all_skills = Skill::get_all_skills(&rfa.db_connection);
-
-                        rfa.input_reps = vec!["".into(); all_skills.len()];
-                        rfa.skills = all_skills;
-                        rfa.menu = Some(Menu::SetReps(false));
                     }
 
-                    if ui
-                        .button(
-                            RichText::new(
-                                rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()),
-                            )
+                    rfa.skills = Skill::get_all_skills(&rfa.db_connection);
+                    rfa.menu = Some(Menu::ImportExport(false));
+                }
+
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
                             .color(CANCEL_COLOR),
-                        )
-                        .clicked()
-                    {
-                        rfa.menu = Some(Menu::SetReps(false));
-                    }
-                });
+                    )
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::ImportExport(false));
+                }
             });
-        }
-    });
+        });
+    }
 }
 
-pub fn view_skills(rfa: &mut RingFitApp, ctx: &Context) {
+pub fn backup(rfa: &mut RingFitApp, ctx: &Context) {
     CentralPanel::default().show(ctx, |ui| {
         if ui
             .button(
@@ -556,22 +2855,134 @@ pub fn view_skills(rfa: &mut RingFitApp, ctx: &Context) {
             )
             .clicked()
         {
+            rfa.status_message = None;
+            rfa.pending_restore = None;
             rfa.menu = None;
         }
         ui.add_space(HEADER_SIZE);
 
+        ui.horizontal(|ui| {
+            ui.label(rfa.menu_names.get("file").unwrap_or(&"File".to_owned()));
+            ui.text_edit_singleline(&mut rfa.backup_path);
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("create_backup")
+                        .unwrap_or(&"Create backup".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.status_message = Some(
+                    match create_backup(&rfa.db_connection, &rfa.data_dir)
+                        .and_then(|b| write_backup(&b, rfa.backup_path.as_ref()))
+                    {
+                        Ok(_) => format!(
+                            "{}: {}",
+                            rfa.menu_names
+                                .get("exported")
+                                .unwrap_or(&"Saved to".to_owned()),
+                            rfa.backup_path
+                        ),
+                        Err(e) => e.to_string(),
+                    },
+                );
+            }
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("load_backup")
+                        .unwrap_or(&"Load backup".to_owned()),
+                )
+                .clicked()
+            {
+                match read_backup(&rfa.db_connection, rfa.backup_path.as_ref()) {
+                    Ok(backup) => {
+                        rfa.status_message = None;
+                        rfa.pending_restore = Some(backup);
+                    }
+                    Err(e) => {
+                        rfa.status_message = Some(e.to_string());
+                        rfa.pending_restore = None;
+                    }
+                }
+            }
+        });
+
+        if let Some(message) = &rfa.status_message {
+            ui.label(message);
+        }
+
+        ui.add_space(HEADER_SIZE);
+
+        let backup = match &rfa.pending_restore {
+            Some(backup) => backup,
+            None => {
+                // Without a backup loaded, we show the automatic ones to pick from.
+                ui.label(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("auto_backups")
+                            .unwrap_or(&"Automatic backups".to_owned()),
+                    )
+                    .size(HEADER_SIZE),
+                );
+                ScrollArea::new([true, true]).show(ui, |ui| {
+                    for path in get_auto_backups(&rfa.data_dir) {
+                        if ui.button(path.display().to_string()).clicked() {
+                            rfa.backup_path = path.display().to_string();
+                        }
+                    }
+                });
+                return;
+            }
+        };
+
+        let diff = match diff_backup(&rfa.db_connection, &rfa.data_dir, backup) {
+            Ok(diff) => diff,
+            Err(e) => {
+                ui.label(e.to_string());
+                return;
+            }
+        };
+
+        ui.label(format!(
+            "{}: {} ➡ {}",
+            rfa.menu_names
+                .get("lang_select")
+                .unwrap_or(&"Language".to_owned()),
+            diff.language.0,
+            diff.language.1
+        ));
+        ui.label(format!(
+            "{}: {} ➡ {}",
+            rfa.menu_names
+                .get("show_workouts")
+                .unwrap_or(&"Workouts".to_owned()),
+            diff.workouts.0,
+            diff.workouts.1
+        ));
+        ui.label(format!(
+            "{}: {} ➡ {}",
+            rfa.menu_names
+                .get("manual_adjustment")
+                .unwrap_or(&"Manual adjustments".to_owned()),
+            diff.adjustments.0,
+            diff.adjustments.1
+        ));
+        ui.add_space(HEADER_SIZE);
+
         ScrollArea::new([true, true]).show(ui, |ui| {
-            Grid::new("view_skills").striped(true).show(ui, |ui| {
-                let default_name = "Invalid".to_owned();
+            Grid::new("restore_preview").striped(true).show(ui, |ui| {
+                let default_value = "Invalid".to_owned();
 
                 let headers = vec![
-                    rfa.menu_names.get("name").unwrap_or(&default_name),
-                    rfa.menu_names.get("hits").unwrap_or(&default_name),
-                    rfa.menu_names.get("level").unwrap_or(&default_name),
-                    rfa.menu_names.get("damage").unwrap_or(&default_name),
-                    rfa.menu_names.get("unlocks").unwrap_or(&default_name),
-                    rfa.menu_names.get("cooldown").unwrap_or(&default_name),
-                    rfa.menu_names.get("hashtags").unwrap_or(&default_name),
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("goal").unwrap_or(&default_value),
                 ];
 
                 for text in headers {
@@ -579,71 +2990,133 @@ pub fn view_skills(rfa: &mut RingFitApp, ctx: &Context) {
                 }
                 ui.end_row();
 
-                for (i, skill) in rfa.skills.iter().enumerate() {
+                for skill_diff in &diff.skills {
+                    let color = match skill_diff.skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(
+                            rfa.skill_names
+                                .get(&skill_diff.skill)
+                                .unwrap_or(&"".to_owned()),
+                        )
+                        .color(color),
+                    );
                     ui.label(
                         RichText::new(format!(
-                            "{}) {}",
-                            i + 1,
-                            rfa.skill_names.get(skill).unwrap_or(&default_name)
+                            "{} ➡ {}",
+                            rfa.locale.format_number(skill_diff.completed_reps.0 as i64),
+                            rfa.locale.format_number(skill_diff.completed_reps.1 as i64)
                         ))
-                        .color(match skill.skill_type {
-                            SkillTypes::Arms => ARMS_COLOR,
-                            SkillTypes::Core => CORE_COLOR,
-                            SkillTypes::Legs => LEGS_COLOR,
-                            SkillTypes::Yoga => YOGA_COLOR,
-                        }),
+                        .color(color),
                     );
                     ui.label(
-                        RichText::new(match skill.hits {
-                            SkillHits::One => "    🎯    ",
-                            SkillHits::Three => "  🎯🎯🎯  ",
-                            SkillHits::Five => "🎯🎯🎯🎯🎯",
-                            SkillHits::Heal => "    ❤    ",
-                        })
-                        .font(FontId::monospace(14.)),
+                        RichText::new(format!(
+                            "{} ➡ {}",
+                            rfa.locale.format_number(skill_diff.goal_reps.0 as i64),
+                            rfa.locale.format_number(skill_diff.goal_reps.1 as i64)
+                        ))
+                        .color(color),
                     );
+                    ui.end_row();
+                }
+            });
 
-                    ui.vertical(|ui| {
-                        for num in [1, 2, 3, 4] {
-                            ui.label(format!("{}", num));
-                        }
-                    });
+            // Custom skills that came after the backup are deleted with their goals.
+            if !diff.removed_skills.is_empty() {
+                ui.add_space(HEADER_SIZE);
+                ui.label(
+                    RichText::new(
+                        rfa.menu_names
+                            .get("deleted_custom_skills")
+                            .unwrap_or(&"Deleted custom skills".to_owned()),
+                    )
+                    .size(HEADER_SIZE),
+                );
+                for skill in &diff.removed_skills {
+                    ui.label(rfa.skill_names.get(skill).unwrap_or(&skill.name));
+                }
+            }
 
-                    ui.vertical(|ui| {
-                        for dmg in skill.damage {
-                            ui.label(dmg.to_string());
-                        }
-                    });
+            ui.add_space(HEADER_SIZE);
 
-                    ui.vertical(|ui| {
-                        for unlock in skill.unlocks {
-                            ui.label(unlock.to_string());
-                        }
-                    });
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("restore")
+                        .unwrap_or(&"Restore".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.menu = Some(Menu::Backup(true));
+            }
+        });
+    });
 
-                    ui.vertical(|ui| {
-                        for time in skill.recharge_time {
-                            ui.label(time.to_string());
-                        }
-                    });
+    if rfa.menu == Some(Menu::Backup(true)) {
+        Window::new(
+            rfa.menu_names
+                .get("confirm_restore")
+                .unwrap_or(&"Confirm Restore".to_owned()),
+        )
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("show_restore")
+                    .unwrap_or(&"Replace everything with this backup?".to_owned()),
+            );
+            ui.add_space(HEADER_SIZE);
 
-                    ui.vertical(|ui| {
-                        for hashtag in &skill.hashtags {
-                            ui.add(
-                                Label::new(RichText::new(match hashtag {
-                                    SkillHashtags::Empty => "",
-                                    _ => rfa.hashtag_names.get(hashtag).unwrap_or(&default_name),
-                                }))
-                                .wrap(false),
-                            );
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("confirm")
+                                .unwrap_or(&"Confirm".to_owned()),
+                        )
+                        .color(CONFIRM_COLOR),
+                    )
+                    .clicked()
+                {
+                    if rfa.auto_backup() {
+                        if let Some(backup) = rfa.pending_restore.take() {
+                            let result = restore_backup(&rfa.db_connection, &rfa.data_dir, &backup);
+
+                            // The language might have changed too, so we reload everything.
+                            *rfa = RingFitApp::new(rfa.data_dir.clone());
+
+                            rfa.status_message = Some(match result {
+                                Ok(_) => rfa
+                                    .menu_names
+                                    .get("restored")
+                                    .unwrap_or(&"Restored the backup".to_owned())
+                                    .clone(),
+                                Err(e) => e.to_string(),
+                            });
                         }
-                    });
+                    }
 
-                    ui.end_row();
+                    rfa.menu = Some(Menu::Backup(false));
+                }
+
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
+                            .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::Backup(false));
                 }
             });
         });
-    });
+    }
 }
 
 pub fn language_choice(rfa: &mut RingFitApp, ctx: &Context) {
@@ -659,31 +3132,33 @@ pub fn language_choice(rfa: &mut RingFitApp, ctx: &Context) {
         }
         ui.add_space(HEADER_SIZE);
 
+        let languages = get_languages(&rfa.db_connection).unwrap_or_default();
+        let current_name = languages
+            .iter()
+            .find(|(l, _)| *l == rfa.language)
+            .map_or_else(|| rfa.language.to_string(), |(_, name)| name.clone());
+
         ComboBox::from_label(
             rfa.menu_names
                 .get("lang_select")
                 .unwrap_or(&"Select a language".to_owned()),
         )
-        .selected_text(format!("{:?}", rfa.language))
+        .selected_text(current_name)
         .show_ui(ui, |ui| {
-            if ui
-                .selectable_value(&mut rfa.language, Languages::English, "English")
-                .clicked()
-            {
-                switch_language(rfa, rfa.language);
-            };
-            if ui
-                .selectable_value(&mut rfa.language, Languages::German, "Deutsch")
-                .clicked()
-            {
-                switch_language(rfa, rfa.language);
-            };
+            for (language, name) in languages {
+                if ui
+                    .selectable_value(&mut rfa.language, language.clone(), name)
+                    .clicked()
+                {
+                    switch_language(rfa, language);
+                };
+            }
         });
     });
 }
 
 pub fn view_workouts(rfa: &mut RingFitApp, ctx: &Context) {
-    let workouts = get_workouts_from_db(&rfa.db_connection);
+    let history = get_history_from_db(&rfa.db_connection);
 
     CentralPanel::default().show(ctx, |ui| {
         if ui
@@ -712,15 +3187,80 @@ pub fn view_workouts(rfa: &mut RingFitApp, ctx: &Context) {
                 }
                 ui.end_row();
 
-                for (time, workout) in workouts {
-                    ui.label(format!(
-                        "{}/{:02}/{:02} - {:02}:{:02}",
-                        time.year(),
-                        time.month(),
-                        time.day(),
-                        time.hour(),
-                        time.minute()
-                    ));
+                for (time, entry) in history {
+                    let workout = match entry {
+                        HistoryEntry::Workout(workout) => workout,
+                        HistoryEntry::Adjustments(adjustments) => {
+                            // Manual adjustments are shown in italics, so you can tell them apart from real workouts.
+                            ui.vertical(|ui| {
+                                ui.label(
+                                    RichText::new(rfa.locale.format_date_time(&time)).italics(),
+                                );
+                                ui.label(
+                                    RichText::new(
+                                        rfa.menu_names
+                                            .get("manual_adjustment")
+                                            .unwrap_or(&"Manual adjustment".to_owned()),
+                                    )
+                                    .italics()
+                                    .weak(),
+                                );
+                            });
+
+                            ui.vertical(|ui| {
+                                for adjustment in &adjustments {
+                                    let color = match adjustment.skill.skill_type {
+                                        SkillTypes::Arms => ARMS_COLOR,
+                                        SkillTypes::Core => CORE_COLOR,
+                                        SkillTypes::Legs => LEGS_COLOR,
+                                        SkillTypes::Yoga => YOGA_COLOR,
+                                    };
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new(
+                                                rfa.skill_names
+                                                    .get(&adjustment.skill)
+                                                    .unwrap_or(&"".to_owned()),
+                                            )
+                                            .color(color)
+                                            .italics(),
+                                        )
+                                        .wrap(false),
+                                    );
+                                }
+                            });
+
+                            ui.vertical(|ui| {
+                                for adjustment in &adjustments {
+                                    let color = match adjustment.skill.skill_type {
+                                        SkillTypes::Arms => ARMS_COLOR,
+                                        SkillTypes::Core => CORE_COLOR,
+                                        SkillTypes::Legs => LEGS_COLOR,
+                                        SkillTypes::Yoga => YOGA_COLOR,
+                                    };
+                                    ui.add(
+                                        Label::new(
+                                            RichText::new(format!(
+                                                "{} ➡ {}",
+                                                rfa.locale.format_number(adjustment.old_reps),
+                                                rfa.locale.format_number(adjustment.new_reps)
+                                            ))
+                                            .color(color)
+                                            .italics(),
+                                        )
+                                        .wrap(false),
+                                    );
+                                }
+                            });
+
+                            ui.end_row();
+                            ui.separator();
+                            ui.end_row();
+                            continue;
+                        }
+                    };
+
+                    ui.label(rfa.locale.format_date_time(&time));
 
                     ui.vertical(|ui| {
                         for (skill, _) in &workout.skill {
@@ -751,12 +3291,44 @@ pub fn view_workouts(rfa: &mut RingFitApp, ctx: &Context) {
                                 SkillTypes::Yoga => YOGA_COLOR,
                             };
                             ui.add(
-                                Label::new(RichText::new(reps.to_string()).color(color))
-                                    .wrap(false),
+                                Label::new(
+                                    RichText::new(rfa.locale.format_number(*reps as i64))
+                                        .color(color),
+                                )
+                                .wrap(false),
                             );
                         }
                     });
 
+                    ui.vertical(|ui| {
+                        if ui
+                            .button(rfa.menu_names.get("edit").unwrap_or(&"Edit".to_owned()))
+                            .clicked()
+                        {
+                            // We pre-fill the inputs with the reps of the workout, so you only have to change the typo.
+                            rfa.input_reps = rfa
+                                .skills
+                                .iter()
+                                .map(|s| match workout.get_reps(s) {
+                                    0 => "".into(),
+                                    reps => reps.to_string(),
+                                })
+                                .collect();
+                            rfa.menu = Some(Menu::EditWorkout(workout.id, false));
+                        }
+                        if ui
+                            .button(
+                                RichText::new(
+                                    rfa.menu_names.get("delete").unwrap_or(&"Delete".to_owned()),
+                                )
+                                .color(CANCEL_COLOR),
+                            )
+                            .clicked()
+                        {
+                            rfa.menu = Some(Menu::ViewWorkouts(Some(workout.id)));
+                        }
+                    });
+
                     ui.end_row();
                     ui.separator();
                     ui.end_row();
@@ -764,4 +3336,233 @@ pub fn view_workouts(rfa: &mut RingFitApp, ctx: &Context) {
             });
         });
     });
+
+    if let Some(Menu::ViewWorkouts(Some(id))) = rfa.menu {
+        Window::new(
+            rfa.menu_names
+                .get("confirm_delete")
+                .unwrap_or(&"Delete Workout".to_owned()),
+        )
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                rfa.menu_names
+                    .get("show_delete_workout")
+                    .unwrap_or(&"Delete this workout?".to_owned()),
+            );
+            ui.add_space(HEADER_SIZE);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("confirm")
+                                .unwrap_or(&"Confirm".to_owned()),
+                        )
+                        .color(CONFIRM_COLOR),
+                    )
+                    .clicked()
+                {
+                    if rfa.auto_backup() {
+                        if let Err(e) = delete_workout_from_db(&rfa.db_connection, id) {
+                            rfa.error_message = Some(e.to_string());
+                        }
+                    }
+
+                    rfa.skills = Skill::get_all_skills(&rfa.db_connection);
+                    rfa.menu = Some(Menu::ViewWorkouts(None));
+                }
+
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
+                            .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::ViewWorkouts(None));
+                }
+            });
+        });
+    }
+}
+
+pub fn edit_workout(rfa: &mut RingFitApp, ctx: &Context) {
+    let id = match rfa.menu {
+        Some(Menu::EditWorkout(id, _)) => id,
+        _ => return,
+    };
+
+    let workout = match get_workout_from_db(&rfa.db_connection, id) {
+        Ok(w) => w,
+        // The workout does not exist anymore, nothing left to edit.
+        Err(_) => {
+            rfa.menu = Some(Menu::ViewWorkouts(None));
+            return;
+        }
+    };
+
+    CentralPanel::default().show(ctx, |ui| {
+        if ui
+            .button(
+                RichText::new(rfa.menu_names.get("back").unwrap_or(&"Back".to_owned()))
+                    .color(BACK_COLOR),
+            )
+            .clicked()
+        {
+            rfa.input_reps = vec!["".into(); rfa.skills.len()];
+            rfa.menu = Some(Menu::ViewWorkouts(None));
+        }
+        ui.add_space(HEADER_SIZE);
+
+        ScrollArea::new([true, true]).show(ui, |ui| {
+            Grid::new("edit_workout").striped(true).show(ui, |ui| {
+                let default_value = "Invalid".to_owned();
+
+                let headers = vec![
+                    rfa.menu_names.get("skill").unwrap_or(&default_value),
+                    rfa.menu_names.get("reps").unwrap_or(&default_value),
+                    rfa.menu_names.get("new_reps").unwrap_or(&default_value),
+                ];
+
+                for text in headers {
+                    ui.label(RichText::new(text).size(HEADER_SIZE));
+                }
+                ui.end_row();
+
+                for (i, skill) in rfa.skills.iter().enumerate() {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(rfa.skill_names.get(skill).unwrap_or(&"".to_owned()))
+                            .color(color),
+                    );
+                    ui.label(RichText::new(workout.get_reps(skill).to_string()).color(color));
+                    ui.text_edit_singleline(&mut rfa.input_reps[i])
+                        .on_hover_text(format!(
+                            "{}: {}",
+                            rfa.menu_names
+                                .get("enter_workout_reps")
+                                .unwrap_or(&"Enter the reps in this workout for".to_owned()),
+                            rfa.skill_names.get(skill).unwrap_or(&"".into())
+                        ));
+
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(HEADER_SIZE);
+
+            if ui
+                .button(
+                    rfa.menu_names
+                        .get("save_changes")
+                        .unwrap_or(&"Save changes".to_owned()),
+                )
+                .clicked()
+            {
+                rfa.menu = Some(Menu::EditWorkout(id, true));
+            }
+        });
+    });
+
+    if rfa.menu == Some(Menu::EditWorkout(id, true)) {
+        Window::new(
+            rfa.menu_names
+                .get("confirm_changes")
+                .unwrap_or(&"Confirm Changes".to_owned()),
+        )
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                RichText::new(
+                    rfa.menu_names
+                        .get("show_edit_workout")
+                        .unwrap_or(&"Save these changes?".to_owned()),
+                )
+                .size(HEADER_SIZE),
+            );
+            ui.add_space(HEADER_SIZE);
+
+            for (i, skill) in rfa.skills.iter().enumerate() {
+                let old_reps = workout.get_reps(skill);
+                let new_reps = rfa.input_reps[i].parse::<usize>().unwrap_or(0);
+
+                // Only the skills that actually change are shown.
+                if old_reps != new_reps {
+                    let color = match skill.skill_type {
+                        SkillTypes::Arms => ARMS_COLOR,
+                        SkillTypes::Core => CORE_COLOR,
+                        SkillTypes::Legs => LEGS_COLOR,
+                        SkillTypes::Yoga => YOGA_COLOR,
+                    };
+                    ui.label(
+                        RichText::new(format!(
+                            "{}: {} ➡ {}",
+                            rfa.skill_names.get(skill).unwrap_or(&"".into()),
+                            old_reps,
+                            new_reps
+                        ))
+                        .color(color),
+                    );
+                }
+            }
+            ui.add_space(HEADER_SIZE);
+
+            ui.horizontal(|ui| {
+                if ui
+                    .button(
+                        RichText::new(
+                            rfa.menu_names
+                                .get("confirm")
+                                .unwrap_or(&"Confirm".to_owned()),
+                        )
+                        .color(CONFIRM_COLOR),
+                    )
+                    .clicked()
+                {
+                    // If anything goes wrong, the changes stay in the form to try again.
+                    if rfa.auto_backup() {
+                        match update_workout_in_db(
+                            &rfa.db_connection,
+                            id,
+                            &rfa.skills,
+                            &rfa.input_reps,
+                        ) {
+                            Ok(_) => {
+                                let all_skills = Skill::get_all_skills(&rfa.db_connection);
+
+                                rfa.input_reps = vec!["".into(); all_skills.len()];
+                                rfa.skills = all_skills;
+                                rfa.menu = Some(Menu::ViewWorkouts(None));
+                            }
+                            Err(e) => {
+                                rfa.error_message = Some(e.to_string());
+                                rfa.menu = Some(Menu::EditWorkout(id, false));
+                            }
+                        }
+                    } else {
+                        rfa.menu = Some(Menu::EditWorkout(id, false));
+                    }
+                }
+
+                if ui
+                    .button(
+                        RichText::new(rfa.menu_names.get("cancel").unwrap_or(&"Cancel".to_owned()))
+                            .color(CANCEL_COLOR),
+                    )
+                    .clicked()
+                {
+                    rfa.menu = Some(Menu::EditWorkout(id, false));
+                }
+            });
+        });
+    }
 }
@@ -0,0 +1,170 @@
+use std::{
+    env,
+    error::Error,
+    fs::{copy, create_dir_all, remove_file},
+    path::{Path, PathBuf},
+};
+
+/// The environment variable that overrides the data directory.
+pub const DATA_DIR_ENV: &str = "RFA_DATA_DIR";
+/// The command line flag that overrides the data directory, takes precedence over the environment variable.
+pub const DATA_DIR_FLAG: &str = "--data-dir";
+
+/// Where the program used to keep its files, relative to the working directory.
+const LEGACY_DATABASE: &str = "./db/database.db";
+const LEGACY_SETTINGS: &str = "./settings/settings.json";
+
+/// The folder all of the data of the program lives in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataDir {
+    pub path: PathBuf,
+}
+
+impl DataDir {
+    #[must_use]
+    /// Uses the given folder as the data directory.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    #[must_use]
+    /// Finds the data directory to use, in this order:
+    /// The command line flag, the environment variable and then the default folder of the platform,
+    /// for example `~/.local/share/rfa-tracker` on Linux or `%APPDATA%\rfa-tracker` on Windows.
+    pub fn resolve(args: &[String]) -> Self {
+        if let Some(path) = get_flag_value(args, DATA_DIR_FLAG) {
+            return Self::new(path);
+        }
+
+        if let Some(path) = env::var_os(DATA_DIR_ENV).filter(|p| !p.is_empty()) {
+            return Self::new(path);
+        }
+
+        match dirs::data_dir() {
+            Some(path) => Self::new(path.join("rfa-tracker")),
+            // If there is no such folder on this platform we fall back to the working directory.
+            None => Self::new("."),
+        }
+    }
+
+    #[must_use]
+    /// The path of the database file.
+    pub fn database(&self) -> PathBuf {
+        self.path.join("database.db")
+    }
+
+    #[must_use]
+    /// The path of the settings file.
+    pub fn settings(&self) -> PathBuf {
+        self.path.join("settings.json")
+    }
+
+    #[must_use]
+    /// The folder the automatic backups are saved in.
+    pub fn backups(&self) -> PathBuf {
+        self.path.join("backups")
+    }
+
+    #[must_use]
+    /// The folder with translation files of the user, for new languages or changed translations.
+    pub fn languages(&self) -> PathBuf {
+        self.path.join("languages")
+    }
+
+    #[must_use]
+    /// Checks if there is a database from older versions in the working directory,
+    /// which is not the database we are using already.
+    pub fn has_legacy_data(&self) -> bool {
+        let legacy = Path::new(LEGACY_DATABASE);
+
+        legacy.is_file()
+            && match (legacy.canonicalize(), self.database().canonicalize()) {
+                (Ok(a), Ok(b)) => a != b,
+                _ => true,
+            }
+    }
+
+    /// Moves the database and settings from older versions in the working directory into the data directory.
+    /// This replaces the files in the data directory, so the connection to the database has to be closed beforehand.
+    pub fn move_legacy_data(&self) -> Result<(), Box<dyn Error>> {
+        create_dir_all(&self.path)?;
+
+        // We copy and remove instead of renaming, renaming does not work across drives.
+        copy(LEGACY_DATABASE, self.database())?;
+        remove_file(LEGACY_DATABASE)?;
+
+        if Path::new(LEGACY_SETTINGS).is_file() {
+            copy(LEGACY_SETTINGS, self.settings())?;
+            remove_file(LEGACY_SETTINGS)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Gets the value of a command line flag, given either as `--flag value` or `--flag=value`.
+#[must_use]
+pub fn get_flag_value(args: &[String], flag: &str) -> Option<String> {
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == flag {
+            return iter.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|a| a.strip_prefix('=')) {
+            return Some(value.into());
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_flag_value() {
+        let args: Vec<String> = vec!["rfa".into(), "--data-dir".into(), "/tmp/rfa".into()];
+        assert_eq!(
+            get_flag_value(&args, DATA_DIR_FLAG),
+            Some("/tmp/rfa".into())
+        );
+
+        let args: Vec<String> = vec!["rfa".into(), "--data-dir=/tmp/rfa".into()];
+        assert_eq!(
+            get_flag_value(&args, DATA_DIR_FLAG),
+            Some("/tmp/rfa".into())
+        );
+
+        let args: Vec<String> = vec!["rfa".into(), "--data-directory".into()];
+        assert_eq!(get_flag_value(&args, DATA_DIR_FLAG), None);
+    }
+
+    #[test]
+    fn test_resolve_flag() {
+        let args: Vec<String> = vec!["rfa".into(), "--data-dir".into(), "/tmp/rfa".into()];
+        let data_dir = DataDir::resolve(&args);
+
+        assert_eq!(data_dir.database(), Path::new("/tmp/rfa/database.db"));
+        assert_eq!(data_dir.settings(), Path::new("/tmp/rfa/settings.json"));
+    }
+
+    #[test]
+    fn test_resolve_env() {
+        let no_flag: Vec<String> = vec!["rfa".into()];
+
+        env::set_var(DATA_DIR_ENV, "/tmp/rfa-env");
+        assert_eq!(DataDir::resolve(&no_flag), DataDir::new("/tmp/rfa-env"));
+
+        // The flag still takes precedence.
+        let args: Vec<String> = vec!["rfa".into(), "--data-dir=/tmp/rfa".into()];
+        assert_eq!(DataDir::resolve(&args), DataDir::new("/tmp/rfa"));
+
+        // An empty variable counts as not set.
+        env::set_var(DATA_DIR_ENV, "");
+        assert_ne!(DataDir::resolve(&no_flag), DataDir::new(""));
+
+        env::remove_var(DATA_DIR_ENV);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    balance::Balance,
+    skills::{Skill, SkillHashtags},
+};
+
+// A rough guess of how long one rep takes, including the breaks between skills.
+pub const SECONDS_PER_REP: usize = 4;
+pub const DEFAULT_SESSION_REPS: usize = 300;
+pub const DEFAULT_MAX_PER_TYPE: usize = 2;
+
+/// How big the suggested session should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionTarget {
+    Reps(usize),
+    Minutes(usize),
+}
+
+impl SessionTarget {
+    #[must_use]
+    /// Gets the amount of reps the session should have in total.
+    pub fn get_reps(&self) -> usize {
+        match self {
+            SessionTarget::Reps(reps) => *reps,
+            SessionTarget::Minutes(minutes) => minutes * 60 / SECONDS_PER_REP,
+        }
+    }
+}
+
+#[must_use]
+/// Gets how important it is to train a skill next.
+/// Skills with more reps left get picked first, and skills training neglected muscle groups are worth up to twice as much.
+pub fn get_priority(skill: &Skill, balance: &Balance) -> f64 {
+    let max_reps = balance
+        .hashtags
+        .iter()
+        .map(|(_, reps)| *reps)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let neglect: Vec<f64> = skill
+        .hashtags
+        .iter()
+        .filter(|h| **h != SkillHashtags::Empty)
+        .map(|h| {
+            let reps = balance
+                .hashtags
+                .iter()
+                .find(|(hashtag, _)| hashtag == h)
+                .map_or(0, |(_, reps)| *reps);
+            1.0 - reps as f64 / max_reps as f64
+        })
+        .collect();
+    let average_neglect = neglect.iter().sum::<f64>() / neglect.len().max(1) as f64;
+
+    skill.get_reps_until_goal() as f64 * (1.0 + average_neglect)
+}
+
+#[must_use]
+/// Suggests the reps to do for every skill, in the same order and format as the inputs in `log_workout`.
+/// At most `max_per_type` skills of each type get picked, and the reps are split by their priority.
+pub fn plan_session(
+    skills: &[Skill],
+    balance: &Balance,
+    target: SessionTarget,
+    max_per_type: usize,
+) -> Vec<String> {
+    let mut ranked: Vec<(usize, f64)> = skills
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.get_reps_until_goal() > 0)
+        .map(|(i, s)| (i, get_priority(s, balance)))
+        .collect();
+    ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let mut picked: Vec<(usize, f64)> = Vec::new();
+    for (i, priority) in ranked {
+        let same_type = picked
+            .iter()
+            .filter(|(j, _)| skills[*j].skill_type == skills[i].skill_type)
+            .count();
+        if same_type < max_per_type {
+            picked.push((i, priority));
+        }
+    }
+
+    let all_priority = picked.iter().map(|(_, p)| p).sum::<f64>();
+    let mut rep_list = vec![String::new(); skills.len()];
+
+    for (i, priority) in picked {
+        let reps = (target.get_reps() as f64 * priority / all_priority).round() as usize;
+        let reps = reps.min(skills[i].get_reps_until_goal());
+
+        if reps > 0 {
+            rep_list[i] = reps.to_string();
+        }
+    }
+
+    rep_list
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{balance::get_balance, skills::all_skills_default, skills::SkillTypes};
+
+    #[test]
+    fn test_plan_session() {
+        let mut skills = all_skills_default();
+        // A finished skill is never suggested.
+        skills[0].completed_reps = skills[0].goal_reps;
+        // A skill far from its goal gets picked first.
+        skills[1].goal_reps *= 10;
+
+        let balance = get_balance(&[], None);
+        let rep_list = plan_session(&skills, &balance, SessionTarget::Reps(400), 1);
+
+        assert!(rep_list[0].is_empty());
+        assert!(!rep_list[1].is_empty());
+
+        let picked: Vec<&Skill> = skills
+            .iter()
+            .zip(rep_list.iter())
+            .filter(|(_, reps)| !reps.is_empty())
+            .map(|(s, _)| s)
+            .collect();
+        assert_eq!(picked.len(), 4);
+        for skill_type in [
+            SkillTypes::Arms,
+            SkillTypes::Core,
+            SkillTypes::Legs,
+            SkillTypes::Yoga,
+        ] {
+            assert_eq!(
+                picked.iter().filter(|s| s.skill_type == skill_type).count(),
+                1
+            );
+        }
+
+        let all_reps = rep_list
+            .iter()
+            .map(|r| r.parse::<usize>().unwrap_or(0))
+            .sum::<usize>();
+        assert!((398..=402).contains(&all_reps));
+
+        assert_eq!(SessionTarget::Minutes(20).get_reps(), 300);
+    }
+}
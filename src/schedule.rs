@@ -0,0 +1,163 @@
+use std::{collections::HashMap, error::Error};
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::skills::Skill;
+
+/// A plan to reach every goal on the same day, by training every skill a bit on each training day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub start_date: NaiveDate,
+    pub target_date: NaiveDate,
+    // Days of the week without any training.
+    pub rest_days: Vec<Weekday>,
+    // The completed reps of every skill when the schedule was made, to know how far along we should be.
+    pub start_reps: HashMap<String, usize>,
+}
+
+impl Schedule {
+    /// Makes a schedule starting with the current reps of every skill.
+    /// Every rest day only counts once, and at least one day of the week has to be left for training.
+    pub fn new(
+        skills: &[Skill],
+        start_date: NaiveDate,
+        target_date: NaiveDate,
+        mut rest_days: Vec<Weekday>,
+    ) -> Result<Self, Box<dyn Error>> {
+        rest_days.sort_by_key(Weekday::num_days_from_monday);
+        rest_days.dedup();
+        if rest_days.len() >= 7 {
+            return Err("A schedule needs at least one training day every week.".into());
+        }
+
+        Ok(Self {
+            start_date,
+            target_date,
+            rest_days,
+            start_reps: skills
+                .iter()
+                .map(|s| (s.name.clone(), s.completed_reps))
+                .collect(),
+        })
+    }
+
+    #[must_use]
+    /// Counts the days from `from` to `to` that aren't rest days, including both.
+    pub fn count_training_days(&self, from: NaiveDate, to: NaiveDate) -> usize {
+        let mut count = 0;
+        let mut day = from;
+
+        while day <= to {
+            if !self.rest_days.contains(&day.weekday()) {
+                count += 1;
+            }
+            day += Duration::days(1);
+        }
+
+        count
+    }
+
+    #[must_use]
+    /// Gets the reps a skill needs on every training day from today on to reach its goal in time.
+    /// This only depends on the current reps, so it changes with every logged workout.
+    pub fn get_daily_reps(&self, skill: &Skill, today: NaiveDate) -> usize {
+        let days_left = self.count_training_days(today, self.target_date);
+
+        // If the target date is over, everything that's left is due now.
+        match days_left {
+            0 => skill.get_reps_until_goal(),
+            _ => (skill.get_reps_until_goal() as f64 / days_left as f64).ceil() as usize,
+        }
+    }
+
+    #[must_use]
+    /// Gets the reps a skill needs every week to reach its goal in time.
+    pub fn get_weekly_reps(&self, skill: &Skill, today: NaiveDate) -> usize {
+        self.get_daily_reps(skill, today)
+            * self.count_training_days(today, today + Duration::days(6))
+    }
+
+    #[must_use]
+    /// Gets the reps a skill should have before today, if it was trained evenly since the schedule was made.
+    pub fn get_expected_reps(&self, skill: &Skill, today: NaiveDate) -> usize {
+        let start_reps = self
+            .start_reps
+            .get(&skill.name)
+            .copied()
+            .unwrap_or(0)
+            .min(skill.goal_reps);
+
+        let all_days = self.count_training_days(self.start_date, self.target_date);
+        let days_done = self.count_training_days(self.start_date, today - Duration::days(1));
+
+        if all_days == 0 || days_done >= all_days {
+            return skill.goal_reps;
+        }
+
+        start_reps + (skill.goal_reps - start_reps) * days_done / all_days
+    }
+
+    #[must_use]
+    /// Gets how many reps a skill is ahead of the schedule, or behind if it's negative.
+    pub fn get_difference(&self, skill: &Skill, today: NaiveDate) -> i64 {
+        skill.completed_reps.min(skill.goal_reps) as i64
+            - self.get_expected_reps(skill, today) as i64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::skills::all_skills_default;
+
+    #[test]
+    fn test_schedule() {
+        let mut skills = all_skills_default();
+        skills[0].goal_reps = 1000;
+        skills[0].completed_reps = 200;
+
+        // Two weeks, from monday to sunday, without training on the weekends.
+        let start = NaiveDate::from_ymd(2022, 10, 3);
+        let target = NaiveDate::from_ymd(2022, 10, 16);
+        let schedule = Schedule::new(
+            &skills,
+            start,
+            target,
+            vec![Weekday::Sat, Weekday::Sun, Weekday::Sat],
+        )
+        .unwrap();
+        assert_eq!(schedule.rest_days, vec![Weekday::Sat, Weekday::Sun]);
+
+        assert_eq!(schedule.count_training_days(start, target), 10);
+        assert_eq!(schedule.get_daily_reps(&skills[0], start), 80);
+        assert_eq!(schedule.get_weekly_reps(&skills[0], start), 400);
+        assert_eq!(schedule.get_difference(&skills[0], start), 0);
+
+        // After a week without training we're behind by five days of training.
+        let next_week = NaiveDate::from_ymd(2022, 10, 10);
+        assert_eq!(schedule.get_expected_reps(&skills[0], next_week), 600);
+        assert_eq!(schedule.get_difference(&skills[0], next_week), -400);
+        assert_eq!(schedule.get_daily_reps(&skills[0], next_week), 160);
+
+        skills[0].completed_reps = 700;
+        assert_eq!(schedule.get_difference(&skills[0], next_week), 100);
+
+        // Once the target date is over, everything left is due.
+        let later = NaiveDate::from_ymd(2022, 11, 1);
+        assert_eq!(schedule.get_daily_reps(&skills[0], later), 300);
+        assert_eq!(schedule.get_expected_reps(&skills[0], later), 1000);
+
+        // Without any training days there is no way to reach the goals.
+        let every_day = vec![
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ];
+        assert!(Schedule::new(&skills, start, target, every_day).is_err());
+    }
+}
@@ -2,18 +2,63 @@ use std::error::Error;
 
 use serde::{Deserialize, Serialize};
 
-use crate::lang::Languages;
+use crate::{
+    forecast::DEFAULT_FORECAST_DAYS, lang::Language, paths::DataDir, schedule::Schedule,
+    stats::DEFAULT_DAY_START_HOUR,
+};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Settings {
-    pub language: Languages,
+    pub language: Language,
+    // How many days back the workouts are used to forecast when the goals are reached.
+    #[serde(default = "default_forecast_days")]
+    pub forecast_days: i64,
+    // The hour a new day starts for the stats, so late workouts count for the day before.
+    #[serde(default = "default_day_start_hour")]
+    pub day_start_hour: u32,
+    // The plan to reach every goal on a target date, if one was made.
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+    // The level of the player in Adventure Mode, if they entered it, to know which skills are unlocked.
+    #[serde(default)]
+    pub player_level: Option<usize>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            forecast_days: DEFAULT_FORECAST_DAYS,
+            day_start_hour: DEFAULT_DAY_START_HOUR,
+            schedule: None,
+            player_level: None,
+        }
+    }
+}
+
+// Settings files from older versions don't have these yet.
+fn default_forecast_days() -> i64 {
+    DEFAULT_FORECAST_DAYS
+}
+
+fn default_day_start_hour() -> u32 {
+    DEFAULT_DAY_START_HOUR
 }
 
 /// Loads the settings from the settings.json file into a the Settings struct.
-pub fn load_settings() -> Result<Settings, Box<dyn Error>> {
-    let file_content = std::fs::read_to_string("./settings/settings.json")?;
+pub fn load_settings(data_dir: &DataDir) -> Result<Settings, Box<dyn Error>> {
+    let file_content = std::fs::read_to_string(data_dir.settings())?;
 
     let settings: Settings = serde_json::from_str(&file_content)?;
 
     Ok(settings)
 }
+
+/// Saves the Settings struct into the settings.json file.
+pub fn save_settings(data_dir: &DataDir, settings: &Settings) -> Result<(), Box<dyn Error>> {
+    let s = serde_json::to_string_pretty(settings)?;
+
+    std::fs::write(data_dir.settings(), s)?;
+
+    Ok(())
+}
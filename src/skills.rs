@@ -1,12 +1,20 @@
 use std::{error::Error, hash::Hash, str::FromStr};
 
 use rusqlite::{
-    types::{FromSql, FromSqlError, ValueRef},
-    Connection,
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef},
+    Connection, ToSql,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::lang::{get_string, Languages};
+use crate::{
+    catalog::get_bundled_catalog,
+    lang::{get_string, Language},
+};
+
+// Every skill in the game has 4 levels.
+pub const MAX_LEVEL: usize = 4;
+// The goal of a custom skill if the user does not set one, the same as most skills in the game.
+pub const DEFAULT_CUSTOM_GOAL: usize = 3000;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -18,7 +26,12 @@ pub struct Skill {
     pub hashtags: [SkillHashtags; 3],
     pub recharge_time: [usize; 4],
     pub goal_reps: usize,
+    // The skill catalog does not have this, every skill starts at 0 reps.
+    #[serde(default)]
     pub completed_reps: usize,
+    // Skills added by the user, which are not in the game.
+    #[serde(default)]
+    pub custom: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
@@ -93,9 +106,7 @@ impl PartialEq for Skill {
     }
 }
 
-impl Eq for Skill {
-    fn assert_receiver_is_total_eq(&self) {}
-}
+impl Eq for Skill {}
 
 // Need to impl Hash manually as we can't derive it when impl Eq manually.
 impl Hash for Skill {
@@ -106,9 +117,9 @@ impl Hash for Skill {
 
 impl SkillHashtags {
     #[must_use]
-    /// Gets the display name for the hashtag, translated.
-    pub fn get_translated_name(&self, connection: &Connection, language: &Languages) -> String {
-        let key = match self {
+    /// Gets the key of the hashtag in the translations.
+    pub fn get_key(&self) -> &'static str {
+        match self {
             Self::Empty => "hashtag_empty",
             Self::Chest => "hashtag_chest",
             Self::UpperArms => "hashtag_upper_arms",
@@ -125,9 +136,14 @@ impl SkillHashtags {
             Self::Back => "hashtag_back",
             Self::Flexibility => "hashtag_flexibility",
             Self::Aerobic => "hashtag_aerobic",
-        };
+        }
+    }
 
-        get_string(connection, language, key.into()).unwrap_or_else(|_| "Invalid".into())
+    #[must_use]
+    /// Gets the display name for the hashtag, translated.
+    pub fn get_translated_name(&self, connection: &Connection, language: &Language) -> String {
+        get_string(connection, language, self.get_key().into())
+            .unwrap_or_else(|_| self.get_key().into())
     }
 
     #[must_use]
@@ -154,32 +170,48 @@ impl SkillHashtags {
     }
 }
 
+/// Some type conversions needed.
+/// Unknown values in the database are an error, so that broken data does not go unnoticed.
 impl FromSql for SkillTypes {
-    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        match value {
-            ValueRef::Text(t) => match std::str::from_utf8(t).unwrap_or("") {
-                "Arms" => Ok(Self::Arms),
-                "Core" => Ok(Self::Core),
-                "Legs" => Ok(Self::Legs),
-                _ => Ok(Self::Yoga),
-            },
-            _ => Err(FromSqlError::InvalidType),
-        }
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        Self::from_str(text)
+            .map_err(|_| FromSqlError::Other(format!("Unknown skill type \"{}\"", text).into()))
+    }
+}
+
+impl ToSql for SkillTypes {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
     }
 }
 
-/// Some type conversions needed.
 impl FromSql for SkillHits {
-    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
-        match value {
-            ValueRef::Text(t) => match std::str::from_utf8(t).unwrap_or("") {
-                "One" => Ok(Self::One),
-                "Three" => Ok(Self::Three),
-                "Five" => Ok(Self::Five),
-                _ => Ok(Self::Heal),
-            },
-            _ => Err(FromSqlError::InvalidType),
-        }
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        Self::from_str(text)
+            .map_err(|_| FromSqlError::Other(format!("Unknown skill hits \"{}\"", text).into()))
+    }
+}
+
+impl ToSql for SkillHits {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
+    }
+}
+
+// Hashtags are saved the way they are shown in game, like "#Upper Arms".
+impl FromSql for SkillHashtags {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+        Self::from_str(text)
+            .map_err(|_| FromSqlError::Other(format!("Unknown hashtag \"{}\"", text).into()))
+    }
+}
+
+impl ToSql for SkillHashtags {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::from(self.to_string()))
     }
 }
 
@@ -257,8 +289,46 @@ impl Skill {
     }
 
     #[must_use]
-    /// Gets the translated name of a skill.
-    pub fn get_translated_name(&self, connection: &Connection, language: &Languages) -> String {
+    /// Creates a skill that is not in the game, like pushups or the movements of the minigames.
+    /// These have no damage, unlocks or recharge time, only what is needed to track them.
+    pub fn new_custom(
+        name: &str,
+        skill_type: SkillTypes,
+        hashtags: [SkillHashtags; 3],
+        goal_reps: Option<usize>,
+    ) -> Self {
+        Self {
+            name: name.trim().to_owned(),
+            skill_type,
+            hits: SkillHits::One,
+            damage: [0, 0, 0, 0],
+            unlocks: [0, 0, 0, 0],
+            hashtags,
+            recharge_time: [0, 0, 0, 0],
+            goal_reps: goal_reps.unwrap_or(DEFAULT_CUSTOM_GOAL),
+            completed_reps: 0,
+            custom: true,
+        }
+    }
+
+    #[must_use]
+    /// Checks if a tier of the skill is unlocked at a player level, starting at tier 1.
+    /// Custom skills are not in the game, so they are always unlocked.
+    pub fn is_tier_unlocked(&self, tier: usize, player_level: usize) -> bool {
+        self.custom || self.unlocks[tier - 1] <= player_level
+    }
+
+    #[must_use]
+    /// Gets how many tiers of the skill are unlocked at a player level, 0 if the skill itself is still locked.
+    pub fn get_unlocked_tiers(&self, player_level: usize) -> usize {
+        (1..=MAX_LEVEL)
+            .filter(|tier| self.is_tier_unlocked(*tier, player_level))
+            .count()
+    }
+
+    #[must_use]
+    /// Gets the key of the skill in the translations.
+    pub fn get_key(&self) -> String {
         let mut key = "skill_".to_string();
         key.push_str(
             self.name
@@ -268,80 +338,159 @@ impl Skill {
                 .as_str(),
         );
 
-        get_string(connection, language, key).unwrap_or_else(|_| "Invalid".into())
+        key
+    }
+
+    #[must_use]
+    /// Gets the translated name of a skill.
+    /// Skills without a translation, like the ones the user added, just keep their name.
+    pub fn get_translated_name(&self, connection: &Connection, language: &Language) -> String {
+        match get_string(connection, language, self.get_key()) {
+            Ok(name) if name != self.get_key() => name,
+            _ => self.name.clone(),
+        }
     }
 
     #[must_use]
     /// Gets all skills in a Vec.
+    /// The skills are checked with `try_get_all_skills` on startup,
+    /// so this only fails if the database gets broken while the program is running.
     pub fn get_all_skills(connection: &Connection) -> Vec<Self> {
-        let mut skills = Vec::new();
+        Self::try_get_all_skills(connection)
+            .unwrap_or_else(|e| panic!("Could not read the skills from the database: {}", e))
+    }
 
+    /// Gets all skills with the stats of every level and their hashtags.
+    /// Anything missing or invalid in the database is an error naming the skill.
+    pub fn try_get_all_skills(connection: &Connection) -> Result<Vec<Self>, Box<dyn Error>> {
+        let mut skills: Vec<Self> = Vec::new();
+
+        let mut stmt = connection
+            .prepare("SELECT name, type, hits, goal_reps, completed_reps, custom FROM skills")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let broken = |e: rusqlite::Error| format!("The skill \"{}\" is broken: {}", name, e);
+
+            skills.push(Self {
+                skill_type: row.get(1).map_err(broken)?,
+                hits: row.get(2).map_err(broken)?,
+                damage: [0, 0, 0, 0],
+                unlocks: [0, 0, 0, 0],
+                hashtags: [
+                    SkillHashtags::Empty,
+                    SkillHashtags::Empty,
+                    SkillHashtags::Empty,
+                ],
+                recharge_time: [0, 0, 0, 0],
+                goal_reps: row.get(3).map_err(broken)?,
+                completed_reps: row.get(4).map_err(broken)?,
+                custom: row.get(5).map_err(broken)?,
+                name,
+            });
+        }
+
+        let mut levels_found = vec![0; skills.len()];
         let mut stmt = connection
-            .prepare("SELECT * FROM skills")
-            .expect("Something went wrong executing SELECT statement.");
-
-        let skill_iter = stmt
-            .query_map([], |row| {
-                Ok(Self {
-                    name: row.get_unwrap(0),
-                    skill_type: row.get_unwrap(1),
-                    hits: row.get_unwrap(2),
-                    // We have to convert the String into an array of usizes again.
-                    damage: {
-                        let result_str: String = row.get_unwrap(3);
-                        // We will always have 4 values separated by commas in the string.
-                        // Except if the user screws something up manually in the database or something.
-                        // So first we initialise an empty result array and then fill it manually.
-                        let mut result_vec = [0, 0, 0, 0];
-                        // We filter empty results, the damage numbers will never be empty but the hashtags below might be.
-                        for (i, j) in result_str.split(',').filter(|s| !s.is_empty()).enumerate() {
-                            let num = j.parse::<usize>().unwrap_or(0);
-                            result_vec[i] = num;
-                        }
-                        result_vec
-                    },
-                    unlocks: {
-                        let result_str: String = row.get_unwrap(4);
-                        let mut result_vec = [0, 0, 0, 0];
-                        for (i, j) in result_str.split(',').filter(|s| !s.is_empty()).enumerate() {
-                            let num = j.parse::<usize>().unwrap_or(0);
-                            result_vec[i] = num;
-                        }
-                        result_vec
-                    },
-                    hashtags: {
-                        let result_str: String = row.get_unwrap(5);
-                        let mut result_vec: [SkillHashtags; 3] = [
-                            SkillHashtags::Empty,
-                            SkillHashtags::Empty,
-                            SkillHashtags::Empty,
-                        ];
-                        for (i, j) in result_str.split(',').filter(|s| !s.is_empty()).enumerate() {
-                            result_vec[i] =
-                                SkillHashtags::from_str(j).unwrap_or(SkillHashtags::Empty);
-                        }
-                        result_vec
-                    },
-                    recharge_time: {
-                        let result_str: String = row.get_unwrap(6);
-                        let mut result_vec = [0, 0, 0, 0];
-                        for (i, j) in result_str.split(',').filter(|s| !s.is_empty()).enumerate() {
-                            let num = j.parse::<usize>().unwrap_or(0);
-                            result_vec[i] = num;
-                        }
-                        result_vec
-                    },
-                    goal_reps: row.get_unwrap(7),
-                    completed_reps: row.get_unwrap(8),
-                })
-            })
-            .expect("Reading data failed.");
-
-        for skill in skill_iter {
-            skills.push(skill.expect("Could not read skill data from database."));
+            .prepare("SELECT skill, level, damage, unlock_level, recharge FROM skill_levels")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let level: usize = row.get(1)?;
+            let broken = |e: rusqlite::Error| {
+                format!("Level {} of the skill \"{}\" is broken: {}", level, name, e)
+            };
+
+            let i = skills
+                .iter()
+                .position(|s| s.name == name)
+                .ok_or_else(|| format!("There are stats for the unknown skill \"{}\".", name))?;
+            let level_index = match level {
+                1..=MAX_LEVEL => level - 1,
+                _ => {
+                    return Err(format!(
+                        "The skill \"{}\" has stats for level {}, but skills only go from level 1 to {}.",
+                        name, level, MAX_LEVEL
+                    )
+                    .into())
+                }
+            };
+
+            skills[i].damage[level_index] = row.get(2).map_err(broken)?;
+            skills[i].unlocks[level_index] = row.get(3).map_err(broken)?;
+            skills[i].recharge_time[level_index] = row.get(4).map_err(broken)?;
+            levels_found[i] += 1;
+        }
+
+        // Custom skills are not in the game, so only they can be without stats.
+        for (skill, levels) in skills.iter().zip(levels_found) {
+            if !skill.custom && levels != MAX_LEVEL {
+                return Err(format!(
+                    "The skill \"{}\" has stats for {} of its {} levels.",
+                    skill.name, levels, MAX_LEVEL
+                )
+                .into());
+            }
+        }
+
+        let mut stmt = connection.prepare("SELECT skill, position, hashtag FROM skill_hashtags")?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let name: String = row.get(0)?;
+            let position: usize = row.get(1)?;
+
+            let skill = skills
+                .iter_mut()
+                .find(|s| s.name == name)
+                .ok_or_else(|| format!("There are hashtags for the unknown skill \"{}\".", name))?;
+            let hashtag = skill
+                .hashtags
+                .get_mut(position.wrapping_sub(1))
+                .ok_or_else(|| {
+                    format!(
+                        "The skill \"{}\" has a hashtag at position {}, but skills only have up to 3.",
+                        name, position
+                    )
+                })?;
+            *hashtag = row
+                .get(2)
+                .map_err(|e| format!("The hashtags of the skill \"{}\" are broken: {}", name, e))?;
         }
 
-        skills
+        Ok(skills)
+    }
+
+    /// Saves the stats of every level and the hashtags of a skill, replacing the ones saved before.
+    /// Custom skills only have hashtags.
+    pub fn save_stats(&self, connection: &Connection) -> Result<(), Box<dyn Error>> {
+        connection.execute("DELETE FROM skill_levels WHERE skill = ?", [&self.name])?;
+        connection.execute("DELETE FROM skill_hashtags WHERE skill = ?", [&self.name])?;
+
+        if !self.custom {
+            for level in 0..MAX_LEVEL {
+                connection.execute(
+                    "INSERT INTO skill_levels VALUES (:skill, :level, :damage, :unlock_level, :recharge)",
+                    (
+                        &self.name,
+                        level + 1,
+                        self.damage[level],
+                        self.unlocks[level],
+                        self.recharge_time[level],
+                    ),
+                )?;
+            }
+        }
+
+        for (i, hashtag) in self.hashtags.iter().enumerate() {
+            if *hashtag != SkillHashtags::Empty {
+                connection.execute(
+                    "INSERT INTO skill_hashtags VALUES (:skill, :position, :hashtag)",
+                    (&self.name, i + 1, hashtag),
+                )?;
+            }
+        }
+
+        Ok(())
     }
 
     /// Increases the reps for a skill by X.
@@ -358,6 +507,20 @@ impl Skill {
         Ok(())
     }
 
+    /// Decreases the reps for a skill by X, but never below 0.
+    pub fn remove_reps(
+        &self,
+        connection: &Connection,
+        reps_removed: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        connection.execute(
+            "UPDATE skills SET completed_reps = MAX(completed_reps - :reps_removed, 0) WHERE name = :name",
+            (reps_removed, self.name.clone()),
+        )?;
+
+        Ok(())
+    }
+
     /// Sets the reps for a skill to X.
     pub fn set_reps(
         &self,
@@ -371,657 +534,28 @@ impl Skill {
 
         Ok(())
     }
+
+    /// Sets the goal for a skill to X reps.
+    pub fn set_goal(
+        &self,
+        connection: &Connection,
+        goal_reps: usize,
+    ) -> Result<(), Box<dyn Error>> {
+        connection.execute(
+            "UPDATE skills SET goal_reps = :goal_reps WHERE name = :name",
+            (goal_reps, self.name.clone()),
+        )?;
+
+        Ok(())
+    }
 }
 
 #[must_use]
-#[allow(clippy::too_many_lines)]
-/// Gets all skills in default form, used when setting up the database for the first time.
-/// This would probably be make more sense in a json file.
+/// Gets all skills in default form from the skill catalog, used when setting up the database for the first time.
 pub fn all_skills_default() -> Vec<Skill> {
-    [
-        Skill {
-            name: "Front Press".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::Three,
-            damage: [25, 320, 390, 745],
-            unlocks: [5, 144, 148, 286],
-            hashtags: [
-                SkillHashtags::Chest,
-                SkillHashtags::Empty,
-                SkillHashtags::Empty,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Press".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::One,
-            damage: [30, 350, 655, 1000],
-            unlocks: [1, 104, 201, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Chest,
-                SkillHashtags::Shoulders,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Back Press".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::One,
-            damage: [220, 255, 675, 100],
-            unlocks: [77, 80, 180, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Posture,
-                SkillHashtags::Shoulders,
-            ],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Tricep Kickback".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::Three,
-            damage: [145, 240, 430, 745],
-            unlocks: [62, 100, 195, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Empty,
-                SkillHashtags::Empty,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Bow Pull".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::Five,
-            damage: [35, 210, 370, 655],
-            unlocks: [17, 107, 156, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Trapezius,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Shoulder Press".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::Heal,
-            damage: [6, 12, 14, 20],
-            unlocks: [52, 119, 156, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Posture,
-                SkillHashtags::Shoulders,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Arm Spin".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::Five,
-            damage: [90, 295, 490, 655],
-            unlocks: [47, 131, 267, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Shoulders,
-                SkillHashtags::Posture,
-            ],
-            recharge_time: [3, 3, 5, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Arm Twist".into(),
-            skill_type: SkillTypes::Arms,
-            hits: SkillHits::One,
-            damage: [90, 350, 705, 1000],
-            unlocks: [29, 125, 188, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Shoulders,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [2, 2, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Plank".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Three,
-            damage: [50, 325, 485, 745],
-            unlocks: [20, 132, 172, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Core,
-                SkillHashtags::Posture,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Leg Raise".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [175, 300, 755, 1000],
-            unlocks: [56, 92, 196, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Core,
-                SkillHashtags::Empty,
-            ],
-            recharge_time: [2, 2, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Open & Close Leg Raise".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Heal,
-            damage: [5, 13, 17, 20],
-            unlocks: [28, 125, 184, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Side Bend".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Heal,
-            damage: [7, 11, 14, 20],
-            unlocks: [65, 119, 146, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Core,
-                SkillHashtags::UpperArms,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Pendulum Bend".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Three,
-            damage: [130, 215, 560, 745],
-            unlocks: [58, 89, 245, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::LowerBody,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [2, 3, 5, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Bend".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [80, 390, 795, 1000],
-            unlocks: [20, 116, 204, 286],
-            hashtags: [
-                SkillHashtags::Core,
-                SkillHashtags::Posture,
-                SkillHashtags::Trapezius,
-            ],
-            recharge_time: [1, 2, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Seated Forward Press".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Heal,
-            damage: [5, 10, 15, 20],
-            unlocks: [37, 95, 159, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Abs,
-                SkillHashtags::Flexibility,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Knee-to-Chest".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [30, 235, 700, 1000],
-            unlocks: [1, 74, 226, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::UpperArms,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Lunge Twist".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [155, 360, 840, 1000],
-            unlocks: [50, 113, 212, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Legs,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [2, 2, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Leg Scissors".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Three,
-            damage: [135, 280, 445, 745],
-            unlocks: [58, 110, 164, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Legs,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Flutter Kick".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [175, 470, 625, 1000],
-            unlocks: [56, 122, 169, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Legs,
-                SkillHashtags::Empty,
-            ],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Seated Ring Raise".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::One,
-            damage: [220, 335, 545, 1000],
-            unlocks: [74, 101, 152, 286],
-            hashtags: [SkillHashtags::Abs, SkillHashtags::Legs, SkillHashtags::Core],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Russian Twist".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Five,
-            damage: [130, 235, 455, 655],
-            unlocks: [61, 103, 233, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Abs,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Standing Twist".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Five,
-            damage: [20, 205, 325, 655],
-            unlocks: [8, 101, 144, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Stamina,
-                SkillHashtags::Empty,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Hip Shake".into(),
-            skill_type: SkillTypes::Core,
-            hits: SkillHits::Five,
-            damage: [70, 275, 395, 655],
-            unlocks: [38, 122, 177, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Stamina,
-                SkillHashtags::UpperArms,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Squat".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::One,
-            damage: [30, 360, 655, 1000],
-            unlocks: [1, 116, 215, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Wide Squat".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Three,
-            damage: [85, 185, 560, 745],
-            unlocks: [35, 77, 250, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [2, 3, 5, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Overhead Squat".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Five,
-            damage: [110, 210, 325, 655],
-            unlocks: [50, 98, 139, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [3, 3, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Thigh Press".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::One,
-            damage: [80, 295, 615, 1000],
-            unlocks: [23, 89, 168, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::LowerBody,
-                SkillHashtags::Posture,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Hip Lift".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Heal,
-            damage: [6, 11, 16, 20],
-            unlocks: [44, 107, 209, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Mountain Climber".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Five,
-            damage: [120, 285, 510, 655],
-            unlocks: [59, 151, 200, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::UpperArms,
-                SkillHashtags::Glutes,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 3000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Knee Lift".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::One,
-            damage: [50, 275, 615, 1000],
-            unlocks: [11, 86, 169, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Legs,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Side Step".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Three,
-            damage: [160, 295, 545, 725],
-            unlocks: [66, 116, 192, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Legs,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Ring Raise Combo".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::One,
-            damage: [155, 415, 615, 1000],
-            unlocks: [44, 122, 165, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Knee-Lift Combo".into(),
-            skill_type: SkillTypes::Legs,
-            hits: SkillHits::Three,
-            damage: [165, 240, 490, 745],
-            unlocks: [71, 110, 180, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::Glutes,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 5000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Chair Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::One,
-            damage: [30, 260, 655, 1000],
-            unlocks: [1, 77, 240, 286],
-            hashtags: [
-                SkillHashtags::LowerBody,
-                SkillHashtags::Core,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Boat Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Five,
-            damage: [155, 320, 495, 655],
-            unlocks: [71, 137, 255, 286],
-            hashtags: [
-                SkillHashtags::Abs,
-                SkillHashtags::Core,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [3, 3, 5, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Standing Forward Fold".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Heal,
-            damage: [8, 11, 19, 20],
-            unlocks: [70, 113, 208, 286],
-            hashtags: [
-                SkillHashtags::UpperArms,
-                SkillHashtags::Shoulders,
-                SkillHashtags::Flexibility,
-            ],
-            recharge_time: [3, 3, 5, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Tree Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::One,
-            damage: [220, 425, 490, 1000],
-            unlocks: [68, 138, 140, 286],
-            hashtags: [
-                SkillHashtags::Legs,
-                SkillHashtags::LowerBody,
-                SkillHashtags::Posture,
-            ],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Hinge Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Three,
-            damage: [125, 350, 460, 745],
-            unlocks: [53, 137, 188, 286],
-            hashtags: [
-                SkillHashtags::Shoulders,
-                SkillHashtags::Legs,
-                SkillHashtags::Back,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Revolved Crescent Lunge Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::One,
-            damage: [130, 295, 580, 1000],
-            unlocks: [41, 84, 160, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::LowerBody,
-                SkillHashtags::Core,
-            ],
-            recharge_time: [2, 2, 3, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Fan Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Heal,
-            damage: [4, 9, 15, 20],
-            unlocks: [26, 83, 185, 286],
-            hashtags: [
-                SkillHashtags::Waist,
-                SkillHashtags::Flexibility,
-                SkillHashtags::Shoulders,
-            ],
-            recharge_time: [3, 3, 4, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Warrior I Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::One,
-            damage: [60, 300, 580, 1000],
-            unlocks: [14, 92, 155, 286],
-            hashtags: [
-                SkillHashtags::LowerBody,
-                SkillHashtags::Aerobic,
-                SkillHashtags::Posture,
-            ],
-            recharge_time: [1, 2, 3, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Warrior II Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Five,
-            damage: [60, 210, 430, 655],
-            unlocks: [32, 95, 176, 286],
-            hashtags: [
-                SkillHashtags::Chest,
-                SkillHashtags::UpperArms,
-                SkillHashtags::Shoulders,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-        Skill {
-            name: "Warrior III Pose".into(),
-            skill_type: SkillTypes::Yoga,
-            hits: SkillHits::Three,
-            damage: [125, 330, 440, 745],
-            unlocks: [44, 128, 162, 286],
-            hashtags: [
-                SkillHashtags::Aerobic,
-                SkillHashtags::Core,
-                SkillHashtags::Stamina,
-            ],
-            recharge_time: [2, 3, 4, 0],
-            goal_reps: 2000,
-            completed_reps: 0,
-        },
-    ]
-    .into()
+    get_bundled_catalog()
+        .expect("The bundled skill catalog is invalid.")
+        .skills
 }
 
 #[cfg(test)]
@@ -1067,6 +601,21 @@ mod test {
         assert_eq!(SkillHits::Five.to_string(), five_string);
     }
 
+    #[test]
+    fn test_hashtag_sql_conv() {
+        let connection = Connection::open_in_memory().unwrap();
+
+        let hashtag: SkillHashtags = connection
+            .query_row("SELECT ?", [SkillHashtags::UpperArms], |r| r.get(0))
+            .unwrap();
+        assert_eq!(hashtag, SkillHashtags::UpperArms);
+
+        let invalid = connection.query_row("SELECT 'Something else'", [], |r| {
+            r.get::<_, SkillHashtags>(0)
+        });
+        assert!(invalid.is_err());
+    }
+
     #[test]
     fn test_skill_equality() {
         let s_one = Skill {
@@ -1083,6 +632,7 @@ mod test {
             recharge_time: [0, 0, 0, 0],
             goal_reps: 1000,
             completed_reps: 10,
+            custom: false,
         };
 
         let s_two = Skill {
@@ -1099,6 +649,7 @@ mod test {
             recharge_time: [0, 2, 0, 0],
             goal_reps: 50,
             completed_reps: 99,
+            custom: false,
         };
 
         let s_three = Skill {
@@ -1115,6 +666,7 @@ mod test {
             recharge_time: [0, 2, 0, 0],
             goal_reps: 50,
             completed_reps: 99,
+            custom: false,
         };
 
         assert_eq!(s_one, s_two);
@@ -1137,6 +689,7 @@ mod test {
             recharge_time: [0, 0, 0, 0],
             goal_reps: 1000,
             completed_reps: 10,
+            custom: false,
         };
 
         assert!((s.get_rep_percent() - 1.0).abs() < f64::EPSILON);
@@ -0,0 +1,156 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+
+use crate::workout::TimedWorkout;
+
+pub const DEFAULT_DAY_START_HOUR: u32 = 4;
+
+/// How consistently the workouts were done.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub workouts: usize,
+    // Days with at least one workout.
+    pub workout_days: usize,
+    // Days without a workout, from the first workout until today.
+    pub rest_days: usize,
+    // Days in a row with a workout, up to today or yesterday.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub workouts_per_week: f64,
+    pub average_reps: f64,
+}
+
+#[must_use]
+/// Gets the day a workout counts towards.
+/// A day starts at `day_start_hour`, so a workout late at night still counts for the day before.
+pub fn get_training_day(time: DateTime<Local>, day_start_hour: u32) -> NaiveDate {
+    (time.naive_local() - Duration::hours(day_start_hour.into())).date()
+}
+
+#[must_use]
+/// Adds up the reps of all workouts on every training day.
+pub fn get_daily_reps(workouts: &[TimedWorkout], day_start_hour: u32) -> HashMap<NaiveDate, usize> {
+    let mut daily_reps = HashMap::new();
+
+    for (time, workout) in workouts {
+        *daily_reps
+            .entry(get_training_day(*time, day_start_hour))
+            .or_insert(0) += workout.skill.iter().map(|(_, reps)| reps).sum::<usize>();
+    }
+
+    daily_reps
+}
+
+#[must_use]
+/// Calculates the stats of every workout up to `today`, which is a training day as well.
+pub fn get_stats(workouts: &[TimedWorkout], day_start_hour: u32, today: NaiveDate) -> Stats {
+    let days: BTreeSet<NaiveDate> = workouts
+        .iter()
+        .map(|(time, _)| get_training_day(*time, day_start_hour))
+        .collect();
+
+    let mut longest_streak = 0;
+    let mut streak = 0;
+    let mut last_day: Option<NaiveDate> = None;
+    for day in &days {
+        streak = match last_day {
+            Some(last) if *day - last == Duration::days(1) => streak + 1,
+            _ => 1,
+        };
+        longest_streak = longest_streak.max(streak);
+        last_day = Some(*day);
+    }
+
+    // The streak is not over yet if there was no workout today so far.
+    let current_streak = match last_day {
+        Some(last) if today - last <= Duration::days(1) => streak,
+        _ => 0,
+    };
+
+    let all_days = match days.iter().next() {
+        Some(first) => ((today - *first).num_days() + 1).max(days.len() as i64) as usize,
+        None => 0,
+    };
+
+    let all_reps = workouts
+        .iter()
+        .flat_map(|(_, workout)| workout.skill.iter().map(|(_, reps)| reps))
+        .sum::<usize>();
+
+    Stats {
+        workouts: workouts.len(),
+        workout_days: days.len(),
+        rest_days: all_days - days.len(),
+        current_streak,
+        longest_streak,
+        // Less than a week of training still counts as one week.
+        workouts_per_week: workouts.len() as f64 / (all_days as f64 / 7.0).max(1.0),
+        average_reps: if workouts.is_empty() {
+            0.0
+        } else {
+            all_reps as f64 / workouts.len() as f64
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout::Workout;
+
+    fn workout_at(time: &str, reps: usize) -> TimedWorkout {
+        let time = time.parse::<DateTime<chrono::FixedOffset>>().unwrap();
+        let skills = crate::skills::all_skills_default();
+
+        (
+            time.with_timezone(&Local),
+            Workout {
+                id: 0,
+                skill: vec![(skills[0].clone(), reps)],
+            },
+        )
+    }
+
+    #[test]
+    fn test_training_day() {
+        let time = workout_at("2022-10-02T01:30:00+00:00", 0).0;
+        let local = time.naive_local();
+
+        assert_eq!(get_training_day(time, 0), local.date());
+        assert_eq!(
+            get_training_day(time, 4),
+            (local - Duration::hours(4)).date()
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let workouts: Vec<TimedWorkout> = ["01", "02", "03", "05", "06", "06"]
+            .iter()
+            .map(|day| workout_at(&format!("2022-10-{}T12:00:00+00:00", day), 100))
+            .collect();
+        let last_day = get_training_day(workouts[5].0, 0);
+
+        let stats = get_stats(&workouts, 0, last_day + Duration::days(1));
+        assert_eq!(stats.workouts, 6);
+        assert_eq!(stats.workout_days, 5);
+        assert_eq!(stats.rest_days, 2);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.current_streak, 2);
+        assert!((stats.workouts_per_week - 6.0).abs() < 1e-9);
+        assert!((stats.average_reps - 100.0).abs() < 1e-9);
+
+        // Two days without a workout end the streak.
+        let stats = get_stats(&workouts, 0, last_day + Duration::days(2));
+        assert_eq!(stats.current_streak, 0);
+
+        let daily_reps = get_daily_reps(&workouts, 0);
+        assert_eq!(daily_reps.len(), 5);
+        assert_eq!(daily_reps.get(&last_day), Some(&200));
+
+        let stats = get_stats(&[], 0, last_day);
+        assert_eq!(stats.current_streak, 0);
+        assert_eq!(stats.rest_days, 0);
+    }
+}
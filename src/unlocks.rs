@@ -0,0 +1,97 @@
+use crate::skills::{Skill, MAX_LEVEL};
+
+// The highest level there is in Adventure Mode.
+pub const MAX_PLAYER_LEVEL: usize = 300;
+
+/// A tier of a skill and the player level it unlocks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unlock {
+    pub skill: Skill,
+    // Starting at 1, like the levels in `view_skills`.
+    pub tier: usize,
+    pub level: usize,
+}
+
+#[must_use]
+/// Gets every tier that is still locked at a player level, in the order they unlock.
+/// Tiers that unlock at the same level keep the order of the skills.
+pub fn get_upcoming_unlocks(skills: &[Skill], player_level: usize) -> Vec<Unlock> {
+    let mut unlocks: Vec<Unlock> = skills
+        .iter()
+        .filter(|s| !s.custom)
+        .flat_map(|skill| {
+            (1..=MAX_LEVEL)
+                .filter(|tier| !skill.is_tier_unlocked(*tier, player_level))
+                .map(|tier| Unlock {
+                    skill: skill.clone(),
+                    tier,
+                    level: skill.unlocks[tier - 1],
+                })
+        })
+        .collect();
+    // The sort is stable, so the order of the skills stays the same within a level.
+    unlocks.sort_by_key(|u| u.level);
+
+    unlocks
+}
+
+#[must_use]
+/// Gets the tiers that unlock at the next level with any unlocks, if there is one.
+pub fn get_next_unlocks(skills: &[Skill], player_level: usize) -> Vec<Unlock> {
+    let upcoming = get_upcoming_unlocks(skills, player_level);
+
+    match upcoming.first() {
+        Some(first) => {
+            let level = first.level;
+            upcoming.into_iter().filter(|u| u.level == level).collect()
+        }
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::skills::{all_skills_default, SkillHashtags, SkillTypes};
+
+    #[test]
+    fn test_unlocks() {
+        let skills = all_skills_default();
+        let squat = skills.iter().find(|s| s.name == "Squat").unwrap();
+
+        // The squat unlocks at 1, 116, 215 and 286.
+        assert_eq!(squat.get_unlocked_tiers(0), 0);
+        assert_eq!(squat.get_unlocked_tiers(1), 1);
+        assert_eq!(squat.get_unlocked_tiers(215), 3);
+        assert!(!squat.is_tier_unlocked(4, 285));
+        assert!(squat.is_tier_unlocked(4, MAX_PLAYER_LEVEL));
+
+        let upcoming = get_upcoming_unlocks(&skills, 1);
+        assert!(upcoming.windows(2).all(|w| w[0].level <= w[1].level));
+        assert!(upcoming.iter().all(|u| u.level > 1));
+        assert!(upcoming
+            .iter()
+            .any(|u| u.skill.name == "Squat" && u.tier == 2 && u.level == 116));
+        assert!(get_upcoming_unlocks(&skills, MAX_PLAYER_LEVEL).is_empty());
+
+        let next = get_next_unlocks(&skills, 115);
+        assert!(!next.is_empty());
+        assert!(next.iter().all(|u| u.level == next[0].level));
+        assert!(next[0].level <= 116);
+        assert!(get_next_unlocks(&skills, MAX_PLAYER_LEVEL).is_empty());
+
+        // Custom skills are never locked, and are not part of the timeline.
+        let pushups = Skill::new_custom(
+            "Pushups",
+            SkillTypes::Arms,
+            [
+                SkillHashtags::Chest,
+                SkillHashtags::Empty,
+                SkillHashtags::Empty,
+            ],
+            None,
+        );
+        assert_eq!(pushups.get_unlocked_tiers(1), MAX_LEVEL);
+        assert!(get_upcoming_unlocks(&[pushups], 1).is_empty());
+    }
+}
@@ -1,66 +1,617 @@
-use std::error::Error;
+use std::{cmp::Reverse, collections::HashMap, error::Error};
 
 use chrono::{DateTime, Local};
 use rusqlite::Connection;
-use serde::{Deserialize, Serialize};
 
 use crate::skills::Skill;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// The reps done for a skill in a month, consisting of the month as "YYYY-MM",
+/// the name of the skill and the sum of the reps.
+type MonthlyReps = (String, String, usize);
+
+/// A workout together with the time it was done.
+pub type TimedWorkout = (DateTime<Local>, Workout);
+
+#[derive(Debug)]
 pub struct Workout {
+    pub id: i64,
     pub skill: Vec<(Skill, usize)>,
 }
 
-/// Saves the workout and time to the database.
-pub fn save_workout_to_db(
-    connection: &Connection,
-    skill_list: Vec<Skill>,
-    rep_list: Vec<String>,
-) -> Result<(), Box<dyn Error>> {
-    let current_time = chrono::offset::Local::now();
+impl Workout {
+    #[must_use]
+    /// Gets the reps done for a skill in this workout, or 0 if the skill was not part of it.
+    pub fn get_reps(&self, skill: &Skill) -> usize {
+        self.skill
+            .iter()
+            .filter(|(s, _)| s == skill)
+            .map(|(_, reps)| reps)
+            .sum()
+    }
+}
+
+/// A manual change to the completed reps of a skill, outside of any workout.
+#[derive(Debug)]
+pub struct Adjustment {
+    pub id: i64,
+    pub skill: Skill,
+    // These are signed, because the total explained by the history can drop below 0
+    // after deleting workouts that were logged before an adjustment.
+    pub old_reps: i64,
+    pub new_reps: i64,
+}
+
+/// A single entry in the history, either a workout or the manual adjustments made at the same time.
+#[derive(Debug)]
+pub enum HistoryEntry {
+    Workout(Workout),
+    Adjustments(Vec<Adjustment>),
+}
 
-    let mut workout = Workout { skill: Vec::new() };
+/// Everything that can go wrong while logging a workout.
+#[derive(Debug)]
+pub enum WorkoutError {
+    /// None of the reps entered are valid, so there is nothing to log.
+    Empty,
+    /// The skill is not in the database.
+    UnknownSkill(String),
+    /// The reps entered for a skill are not a number.
+    InvalidReps(String, String),
+    Database(rusqlite::Error),
+}
+
+impl std::fmt::Display for WorkoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "The workout does not contain any reps."),
+            Self::UnknownSkill(name) => write!(f, "The skill \"{}\" does not exist.", name),
+            Self::InvalidReps(name, reps) => write!(
+                f,
+                "The reps \"{}\" entered for \"{}\" are not a number.",
+                reps, name
+            ),
+            Self::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl Error for WorkoutError {}
+
+impl From<rusqlite::Error> for WorkoutError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+/// Builds a workout out of the list of skills and the reps entered for each of them.
+/// Skills without any reps entered are left out, anything else that is not a number is an error.
+fn build_workout(skill_list: &[Skill], rep_list: &[String]) -> Result<Workout, WorkoutError> {
+    let mut workout = Workout {
+        id: 0,
+        skill: Vec::new(),
+    };
 
     for (skill, reps) in skill_list.iter().zip(rep_list.iter()) {
-        let rep_count = reps.parse::<usize>().unwrap_or(0);
+        let reps = reps.trim();
+        if reps.is_empty() {
+            continue;
+        }
+
+        let rep_count = reps
+            .parse::<usize>()
+            .map_err(|_| WorkoutError::InvalidReps(skill.name.clone(), reps.to_owned()))?;
         if rep_count != 0 {
             workout.skill.push((skill.to_owned(), rep_count));
         }
     }
 
-    let v = serde_json::to_value(workout)?;
+    Ok(workout)
+}
+
+/// Saves the skills and reps of a workout into the database.
+fn save_workout_skills(
+    connection: &Connection,
+    id: i64,
+    workout: &Workout,
+) -> rusqlite::Result<()> {
+    for (skill, reps) in &workout.skill {
+        connection.execute(
+            "INSERT INTO workout_skills VALUES (:workout_id, :skill, :reps)",
+            (id, skill.name.clone(), reps),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Logs a workout: increases the completed reps of every skill and saves the workout with the current time.
+/// All of it happens in a single transaction, so either everything is saved or nothing is.
+/// Returns the id of the new workout.
+pub fn log_workout_to_db(
+    connection: &Connection,
+    skill_list: &[Skill],
+    rep_list: &[String],
+) -> Result<i64, WorkoutError> {
+    let current_time = chrono::offset::Local::now();
+
+    let workout = build_workout(skill_list, rep_list)?;
+
+    if workout.skill.is_empty() {
+        return Err(WorkoutError::Empty);
+    }
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for (skill, reps) in &workout.skill {
+        let updated = transaction.execute(
+            "UPDATE skills SET completed_reps = completed_reps + :reps WHERE name = :name",
+            (reps, skill.name.clone()),
+        )?;
+
+        if updated == 0 {
+            // Dropping the transaction without committing rolls back everything done so far.
+            return Err(WorkoutError::UnknownSkill(skill.name.clone()));
+        }
+    }
+
+    let id = insert_workout_to_db(&transaction, current_time, &workout)?;
+
+    transaction.commit()?;
 
+    Ok(id)
+}
+
+/// Saves a workout done at the given time to the database and returns its new id.
+/// This does not touch the completed reps of the skills, see `log_workout_to_db` for that.
+/// This is made up of several statements, so it should run inside of a transaction.
+pub fn insert_workout_to_db(
+    connection: &Connection,
+    timestamp: DateTime<Local>,
+    workout: &Workout,
+) -> rusqlite::Result<i64> {
     connection.execute(
-        "INSERT INTO workouts VALUES (:timestamp, :workout)",
-        (current_time, v),
+        "INSERT INTO workouts (timestamp) VALUES (:timestamp)",
+        [timestamp],
     )?;
+    let id = connection.last_insert_rowid();
+    save_workout_skills(connection, id, workout)?;
+
+    Ok(id)
+}
+
+/// Saves a manual change of the completed reps of a skill into the history.
+pub fn save_adjustment_to_db(
+    connection: &Connection,
+    timestamp: DateTime<Local>,
+    skill: &Skill,
+    old_reps: i64,
+    new_reps: i64,
+) -> Result<(), Box<dyn Error>> {
+    connection.execute(
+        "INSERT INTO rep_adjustments (timestamp, skill, old_reps, new_reps) VALUES (:timestamp, :skill, :old_reps, :new_reps)",
+        (timestamp, skill.name.clone(), old_reps, new_reps),
+    )?;
+
+    Ok(())
+}
+
+/// Sets the completed reps of every skill with a valid input to the reps entered,
+/// and records every change as a manual adjustment in the history.
+pub fn save_manual_reps_to_db(
+    connection: &Connection,
+    skill_list: &[Skill],
+    rep_list: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let current_time = chrono::offset::Local::now();
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for (skill, reps) in skill_list.iter().zip(rep_list.iter()) {
+        if let Ok(total_reps) = reps.parse::<usize>() {
+            if total_reps != skill.completed_reps {
+                save_adjustment_to_db(
+                    &transaction,
+                    current_time,
+                    skill,
+                    skill.completed_reps as i64,
+                    total_reps as i64,
+                )?;
+                skill.set_reps(&transaction, total_reps)?;
+            }
+        }
+    }
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Gets every manual adjustment from the database, the newest ones first.
+pub fn get_adjustments_from_db(connection: &Connection) -> Vec<(DateTime<Local>, Adjustment)> {
+    let all_skills = Skill::get_all_skills(connection);
+
+    let mut adjustments = Vec::new();
+
+    let mut stmt = connection
+        .prepare("SELECT id, timestamp, skill, old_reps, new_reps FROM rep_adjustments ORDER BY timestamp DESC, id")
+        .expect("Something went wrong executing SELECT statement.");
+
+    let adjustment_iter = stmt
+        .query_map([], |row| {
+            let time: DateTime<Local> = row.get_unwrap(1);
+            let name: String = row.get_unwrap(2);
+            Ok((
+                time,
+                name,
+                row.get_unwrap(0),
+                row.get_unwrap(3),
+                row.get_unwrap(4),
+            ))
+        })
+        .expect("Reading data failed.");
+
+    for a in adjustment_iter {
+        let (time, name, id, old_reps, new_reps) =
+            a.expect("Error reading adjustment from database.");
+        if let Some(skill) = all_skills.iter().find(|s| s.name == name) {
+            adjustments.push((
+                time,
+                Adjustment {
+                    id,
+                    skill: skill.clone(),
+                    old_reps,
+                    new_reps,
+                },
+            ));
+        }
+    }
+
+    adjustments
+}
+
+/// Gets the whole history, workouts and manual adjustments, the newest entries first.
+/// Adjustments made at the same time are grouped together into one entry.
+pub fn get_history_from_db(connection: &Connection) -> Vec<(DateTime<Local>, HistoryEntry)> {
+    let mut history: Vec<(DateTime<Local>, HistoryEntry)> = get_workouts_from_db(connection)
+        .into_iter()
+        .map(|(time, workout)| (time, HistoryEntry::Workout(workout)))
+        .collect();
+
+    let mut adjustments: Vec<(DateTime<Local>, Vec<Adjustment>)> = Vec::new();
+    for (time, adjustment) in get_adjustments_from_db(connection) {
+        match adjustments.last_mut() {
+            Some((last_time, group)) if *last_time == time => group.push(adjustment),
+            _ => adjustments.push((time, vec![adjustment])),
+        }
+    }
+
+    history.extend(
+        adjustments
+            .into_iter()
+            .map(|(time, group)| (time, HistoryEntry::Adjustments(group))),
+    );
+
+    // The sort is stable, so workouts and adjustments keep their order among themselves.
+    history.sort_by_key(|(time, _)| Reverse(*time));
+
+    history
+}
+
+/// Gets a single workout from the database.
+pub fn get_workout_from_db(connection: &Connection, id: i64) -> Result<Workout, Box<dyn Error>> {
+    // Making sure the workout exists, a workout without any skills would still be valid here.
+    connection.query_row("SELECT id FROM workouts WHERE id = :id", [id], |_| Ok(()))?;
+
+    let all_skills = Skill::get_all_skills(connection);
+
+    let mut stmt =
+        connection.prepare("SELECT skill, reps FROM workout_skills WHERE workout_id = :id")?;
+
+    let mut workout = Workout {
+        id,
+        skill: Vec::new(),
+    };
+
+    for row in stmt.query_map([id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, usize>(1)?))
+    })? {
+        let (name, reps) = row?;
+        if let Some(skill) = all_skills.iter().find(|s| s.name == name) {
+            workout.skill.push((skill.clone(), reps));
+        }
+    }
+
+    Ok(workout)
+}
+
+/// Replaces the reps of a saved workout with the new reps entered
+/// and adjusts the completed reps of every affected skill by the difference.
+/// If no reps are left in the workout, it gets deleted instead.
+pub fn update_workout_in_db(
+    connection: &Connection,
+    id: i64,
+    skill_list: &[Skill],
+    rep_list: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let new_workout = build_workout(skill_list, rep_list)?;
+
+    if new_workout.skill.is_empty() {
+        return delete_workout_from_db(connection, id);
+    }
+
+    // Everything happens in one transaction, so that the skill totals and the workout never disagree.
+    let transaction = connection.unchecked_transaction()?;
+
+    // The old reps come from the rows saved for the workout, so that skills which are not
+    // in the list anymore, like deleted custom skills, are rolled back as well.
+    transaction.query_row("SELECT id FROM workouts WHERE id = :id", [id], |_| Ok(()))?;
+    let mut differences: HashMap<String, i64> = HashMap::new();
+    {
+        let mut stmt =
+            transaction.prepare("SELECT skill, reps FROM workout_skills WHERE workout_id = :id")?;
+        for row in stmt.query_map([id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })? {
+            let (name, reps) = row?;
+            *differences.entry(name).or_default() -= reps;
+        }
+    }
+    for (skill, reps) in &new_workout.skill {
+        *differences.entry(skill.name.clone()).or_default() += *reps as i64;
+    }
+
+    for (name, difference) in differences {
+        transaction.execute(
+            "UPDATE skills SET completed_reps = MAX(completed_reps + :difference, 0) WHERE name = :name",
+            (difference, name),
+        )?;
+    }
+
+    transaction.execute("DELETE FROM workout_skills WHERE workout_id = :id", [id])?;
+    save_workout_skills(&transaction, id, &new_workout)?;
+
+    transaction.commit()?;
+
+    Ok(())
+}
+
+/// Deletes a saved workout and subtracts its reps from the completed reps of the skills.
+pub fn delete_workout_from_db(connection: &Connection, id: i64) -> Result<(), Box<dyn Error>> {
+    let workout = get_workout_from_db(connection, id)?;
+
+    let transaction = connection.unchecked_transaction()?;
+
+    for (skill, reps) in &workout.skill {
+        skill.remove_reps(&transaction, *reps)?;
+    }
+
+    transaction.execute("DELETE FROM workout_skills WHERE workout_id = :id", [id])?;
+    transaction.execute("DELETE FROM workouts WHERE id = :id", [id])?;
+
+    transaction.commit()?;
 
     Ok(())
 }
 
 /// Gets the workouts from the database and returns it together with the local time.
 pub fn get_workouts_from_db(connection: &Connection) -> Vec<(DateTime<Local>, Workout)> {
+    let all_skills = Skill::get_all_skills(connection);
+
+    // First we collect the skills done in every workout, sorted by the workout they belong to.
+    let mut workout_skills: HashMap<i64, Vec<(Skill, usize)>> = HashMap::new();
+
+    let mut stmt = connection
+        .prepare("SELECT workout_id, skill, reps FROM workout_skills ORDER BY rowid")
+        .expect("Something went wrong executing SELECT statement.");
+
+    let skill_iter = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get_unwrap(0);
+            let name: String = row.get_unwrap(1);
+            let reps: usize = row.get_unwrap(2);
+            Ok((id, name, reps))
+        })
+        .expect("Reading data failed.");
+
+    for s in skill_iter {
+        let (id, name, reps) = s.expect("Error reading workout from database.");
+        if let Some(skill) = all_skills.iter().find(|s| s.name == name) {
+            workout_skills
+                .entry(id)
+                .or_default()
+                .push((skill.clone(), reps));
+        }
+    }
+
     let mut workouts = Vec::new();
 
+    // The newest workouts should come first.
     let mut stmt = connection
-        .prepare("SELECT * FROM workouts")
+        .prepare("SELECT id, timestamp FROM workouts ORDER BY timestamp DESC, id DESC")
         .expect("Something went wrong executing SELECT statement.");
 
     let workout_iter = stmt
         .query_map([], |row| {
-            let v: Workout = serde_json::from_value(row.get_unwrap(1))
-                .expect("Error reading workout from database.");
-            let time: DateTime<Local> = row.get_unwrap(0);
-            Ok((time, v))
+            let id: i64 = row.get_unwrap(0);
+            let time: DateTime<Local> = row.get_unwrap(1);
+            Ok((time, id))
         })
         .expect("Reading data failed.");
 
     for w in workout_iter {
-        workouts.push(w.expect("Error reading workout from database."));
+        let (time, id) = w.expect("Error reading workout from database.");
+        workouts.push((
+            time,
+            Workout {
+                id,
+                skill: workout_skills.remove(&id).unwrap_or_default(),
+            },
+        ));
     }
 
-    // The newest workouts should come first.
-    workouts.reverse();
-
     workouts
 }
+
+/// Gets the reps done per skill and per month, straight from the database, the newest month first.
+pub fn get_monthly_reps(connection: &Connection) -> Result<Vec<MonthlyReps>, Box<dyn Error>> {
+    let mut stmt = connection.prepare(
+        "
+            SELECT strftime('%Y-%m', w.timestamp, 'localtime') AS month, s.skill, SUM(s.reps)
+            FROM workout_skills s JOIN workouts w ON w.id = s.workout_id
+            GROUP BY month, s.skill
+            ORDER BY month DESC, s.skill
+        ",
+    )?;
+
+    let monthly_reps = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(monthly_reps)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::test::test_connection;
+
+    fn rep_list(connection: &Connection, reps: &[(&str, usize)]) -> Vec<String> {
+        Skill::get_all_skills(connection)
+            .iter()
+            .map(|s| {
+                reps.iter()
+                    .find(|(name, _)| *name == s.name)
+                    .map(|(_, r)| r.to_string())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    fn completed_reps(connection: &Connection, name: &str) -> usize {
+        Skill::get_all_skills(connection)
+            .into_iter()
+            .find(|s| s.name == name)
+            .unwrap()
+            .completed_reps
+    }
+
+    #[test]
+    fn test_log_workout() {
+        let connection = test_connection();
+
+        let skills = Skill::get_all_skills(&connection);
+
+        let id = log_workout_to_db(
+            &connection,
+            &skills,
+            &rep_list(&connection, &[("Squat", 100), ("Plank", 30)]),
+        )
+        .unwrap();
+
+        assert_eq!(completed_reps(&connection, "Squat"), 100);
+        assert_eq!(completed_reps(&connection, "Plank"), 30);
+        assert_eq!(get_workout_from_db(&connection, id).unwrap().skill.len(), 2);
+
+        assert!(matches!(
+            log_workout_to_db(&connection, &skills, &rep_list(&connection, &[])),
+            Err(WorkoutError::Empty)
+        ));
+
+        // A skill that is not in the database rolls back the whole workout.
+        let mut unknown_skills = skills.clone();
+        unknown_skills[1].name = "Unknown Skill".into();
+        let mut reps = vec!["".to_owned(); skills.len()];
+        reps[0] = "10".into();
+        reps[1] = "10".into();
+
+        assert!(matches!(
+            log_workout_to_db(&connection, &unknown_skills, &reps),
+            Err(WorkoutError::UnknownSkill(_))
+        ));
+        assert_eq!(get_workouts_from_db(&connection).len(), 1);
+        assert_eq!(
+            completed_reps(&connection, &skills[0].name),
+            skills[0].completed_reps
+        );
+    }
+
+    #[test]
+    fn test_manual_reps_history() {
+        let connection = test_connection();
+
+        let skills = Skill::get_all_skills(&connection);
+        log_workout_to_db(
+            &connection,
+            &skills,
+            &rep_list(&connection, &[("Squat", 100)]),
+        )
+        .unwrap();
+        // The second input is the same as before, so it is not an adjustment.
+        save_manual_reps_to_db(
+            &connection,
+            &skills,
+            &rep_list(&connection, &[("Plank", 300), ("Squat", 0)]),
+        )
+        .unwrap();
+
+        assert_eq!(completed_reps(&connection, "Plank"), 300);
+
+        let history = get_history_from_db(&connection);
+        assert_eq!(history.len(), 2);
+
+        match &history[0].1 {
+            HistoryEntry::Adjustments(adjustments) => {
+                assert_eq!(adjustments.len(), 1);
+                assert_eq!(adjustments[0].skill.name, "Plank");
+                assert_eq!(adjustments[0].old_reps, 0);
+                assert_eq!(adjustments[0].new_reps, 300);
+            }
+            HistoryEntry::Workout(_) => panic!("Expected the adjustment to be the newest entry."),
+        }
+        assert!(matches!(history[1].1, HistoryEntry::Workout(_)));
+    }
+
+    #[test]
+    fn test_edit_and_delete_workout() {
+        let connection = test_connection();
+
+        let skills = Skill::get_all_skills(&connection);
+        let reps = rep_list(&connection, &[("Squat", 100), ("Plank", 30)]);
+        let id = log_workout_to_db(&connection, &skills, &reps).unwrap();
+        assert_eq!(completed_reps(&connection, "Squat"), 100);
+
+        // A typo is an error instead of leaving the skill out.
+        let mut typo = rep_list(&connection, &[("Squat", 80)]);
+        typo[skills.iter().position(|s| s.name == "Plank").unwrap()] = "3O".into();
+        assert!(update_workout_in_db(&connection, id, &skills, &typo).is_err());
+        assert!(matches!(
+            log_workout_to_db(&connection, &skills, &typo),
+            Err(WorkoutError::InvalidReps(_, _))
+        ));
+        assert_eq!(completed_reps(&connection, "Plank"), 30);
+
+        // Plank is not in the list of skills anymore, but its reps are still taken back.
+        let new_reps = rep_list(&connection, &[("Squat", 80), ("Front Press", 20)]);
+        let (listed_skills, listed_reps): (Vec<Skill>, Vec<String>) = skills
+            .iter()
+            .cloned()
+            .zip(new_reps)
+            .filter(|(s, _)| s.name != "Plank")
+            .unzip();
+        update_workout_in_db(&connection, id, &listed_skills, &listed_reps).unwrap();
+
+        let workout = get_workout_from_db(&connection, id).unwrap();
+        assert_eq!(workout.skill.len(), 2);
+        assert_eq!(get_monthly_reps(&connection).unwrap().len(), 2);
+        assert_eq!(completed_reps(&connection, "Squat"), 80);
+        assert_eq!(completed_reps(&connection, "Plank"), 0);
+        assert_eq!(completed_reps(&connection, "Front Press"), 20);
+
+        delete_workout_from_db(&connection, id).unwrap();
+
+        assert!(get_workouts_from_db(&connection).is_empty());
+        assert!(get_monthly_reps(&connection).unwrap().is_empty());
+        assert_eq!(completed_reps(&connection, "Squat"), 0);
+        assert_eq!(completed_reps(&connection, "Front Press"), 0);
+    }
+}